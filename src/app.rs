@@ -0,0 +1,1568 @@
+//! The app state: the shortcuts the provider workers send, the matches of the query and what is being opened
+
+use core::fmt;
+use std::{
+  collections::{ BTreeMap, HashMap, HashSet },
+  fmt::{ Display, Formatter },
+  fs,
+  ops::Range,
+  path::{ Path, PathBuf },
+  time::Duration,
+};
+use clap::ValueEnum;
+use crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+use serde::Deserialize;
+use tracing::{ debug, error, info };
+use tui_textarea::TextArea;
+use crate::{
+  config::{ Config, LoadConfigError, Order },
+  data_dir,
+  favorites::Favorites,
+  instance::{ Instance, Summon },
+  launcher::{ raise_terminal_window, History, Launch, Launcher, SystemLauncher },
+  providers::{ build_providers, Provider },
+  replay::Replay,
+  search::{ fold, ShortcutsTrait },
+  shortcut::{ replace_placeholders, OpenTarget, SharedShortcut, Shortcut, ShortcutKind },
+  write_cache,
+};
+
+/// Everything that makes bullet exit without opening a shortcut
+#[derive(Debug)]
+pub enum BulletError {
+  Config(LoadConfigError),
+  NoMatch(String),
+  AmbiguousMatch(String, usize),
+  LaunchFailed(String, std::io::Error),
+  Cancelled,
+  /// The ui panicked, with the panic message
+  Crashed(String),
+  /// `bullet daemon` could not listen for its hotkey
+  Daemon(String),
+  /// A `bullet://` link that doesn't name something to open
+  InvalidUri(String),
+  /// `bullet sync` could not exchange the config with the remote
+  Sync(String),
+  /// `bullet doctor` found checks that failed, how many
+  DoctorFailed(usize),
+  /// The launch history could not be read or cleared
+  History(String),
+  /// `bullet uri install` could not register the bullet:// scheme
+  UriInstall(std::io::Error),
+  /// A file could not be read or written, with what was being done
+  Io(String, std::io::Error),
+  /// Something bullet reads besides the config doesn't parse, with what it is
+  Parse(String, String),
+}
+
+impl BulletError {
+  /// Process exit code, distinct per failure so wrapping scripts can react to it
+  pub fn exit_code(&self) -> i32 {
+    match self {
+      BulletError::Config(LoadConfigError::ParseError(_)) | BulletError::Parse(..) => 3,
+      BulletError::Config(_) => 2,
+      BulletError::NoMatch(_) => 4,
+      BulletError::AmbiguousMatch(..) => 5,
+      BulletError::LaunchFailed(..) => 6,
+      BulletError::Cancelled => 130,
+      BulletError::Crashed(_) => 101,
+      BulletError::Daemon(_) => 7,
+      BulletError::InvalidUri(_) => 8,
+      BulletError::Sync(_) => 9,
+      BulletError::DoctorFailed(_) => 10,
+      BulletError::History(_) => 11,
+      BulletError::UriInstall(_) => 12,
+      BulletError::Io(..) => 13,
+    }
+  }
+  /// Stable identifier used in `--json-errors` output
+  pub fn kind(&self) -> &'static str {
+    match self {
+      BulletError::Config(LoadConfigError::ParseError(_)) | BulletError::Parse(..) => "parse_error",
+      BulletError::Config(_) => "no_config",
+      BulletError::NoMatch(_) => "no_match",
+      BulletError::AmbiguousMatch(..) => "ambiguous_match",
+      BulletError::LaunchFailed(..) => "launch_failed",
+      BulletError::Cancelled => "cancelled",
+      BulletError::Crashed(_) => "crashed",
+      BulletError::Daemon(_) => "daemon_failed",
+      BulletError::InvalidUri(_) => "invalid_uri",
+      BulletError::Sync(_) => "sync_failed",
+      BulletError::DoctorFailed(_) => "doctor_failed",
+      BulletError::History(_) => "history_failed",
+      BulletError::UriInstall(_) => "uri_install_failed",
+      BulletError::Io(..) => "io_failed",
+    }
+  }
+  pub fn report(&self, json: bool) {
+    if json {
+      let error = serde_json::json!({
+        "error": self.kind(),
+        "code": self.exit_code(),
+        "message": self.to_string(),
+      });
+      eprintln!("{error}");
+    } else {
+      eprintln!("bullet: {self}");
+    }
+  }
+}
+
+impl Display for BulletError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      BulletError::Config(e) => write!(f, "failed to load config: {e}"),
+      BulletError::NoMatch(search) => write!(f, "no shortcut matches \"{search}\""),
+      BulletError::AmbiguousMatch(search, count) =>
+        write!(f, "{count} shortcuts match \"{search}\", none of them exactly"),
+      BulletError::LaunchFailed(seq, e) => write!(f, "failed to open \"{seq}\": {e}"),
+      BulletError::Cancelled => write!(f, "cancelled"),
+      BulletError::Crashed(message) => write!(f, "the ui crashed: {message}"),
+      BulletError::Daemon(message) => write!(f, "the daemon failed: {message}"),
+      BulletError::InvalidUri(message) => write!(f, "invalid link: {message}"),
+      BulletError::Sync(message) => write!(f, "failed to sync the config: {message}"),
+      BulletError::DoctorFailed(1) => write!(f, "1 check failed"),
+      BulletError::DoctorFailed(failures) => write!(f, "{failures} checks failed"),
+      BulletError::History(message) => write!(f, "failed to use the history: {message}"),
+      BulletError::UriInstall(e) => write!(f, "failed to register the bullet:// scheme: {e}"),
+      BulletError::Io(what, e) => write!(f, "failed to {what}: {e}"),
+      BulletError::Parse(what, e) => write!(f, "failed to parse {what}: {e}"),
+    }
+  }
+}
+
+/// What a provider's worker thread sends to the app
+enum ProviderMessage {
+  /// Shortcuts of the provider at that index, sent when its worker starts and again on every refresh
+  Shortcuts(usize, Vec<Shortcut>),
+  /// Results of `search` for the query with that id
+  Results(u64, Vec<Shortcut>),
+}
+
+/// What the app asks of a provider's worker thread
+enum WorkerRequest {
+  /// Search the query with that id
+  Search(u64, String),
+  /// Collect the shortcuts again
+  Refresh,
+}
+
+fn shortcuts_cache_path(provider: &dyn Provider) -> Option<PathBuf> {
+  data_dir().map(|dir| dir.join(format!("{}_shortcuts.json", provider.name())))
+}
+
+/// Collects the shortcuts of a provider, keeping them in its cache when it has one
+pub fn collect_shortcuts(provider: &dyn Provider) -> Vec<Shortcut> {
+  let mut shortcuts = provider.shortcuts();
+  debug!(provider = provider.name(), shortcuts = shortcuts.len(), "provided shortcuts");
+  if let (true, Some(path)) = (provider.cached(), shortcuts_cache_path(provider)) {
+    if let Ok(content) = serde_json::to_string(&shortcuts) {
+      write_cache(&path, &content);
+    }
+  }
+  mark_missing(&mut shortcuts);
+  shortcuts
+}
+
+/// Sets `missing` on Dirs, Files and Apps given by path whose path isn't there
+fn mark_missing(shortcuts: &mut [Shortcut]) {
+  for s in shortcuts {
+    let path = s.prefixed_path();
+    s.missing = match s.kind {
+      ShortcutKind::Dir | ShortcutKind::File => !path.exists(),
+      ShortcutKind::App if path.is_absolute() => !path.exists(),
+      _ => false,
+    };
+  }
+}
+
+/// Runs a provider on its own thread, so slow ones never hold up typing. It sends the cached shortcuts,
+/// collects them again, then searches the queries sent to it, skipping to the newest when several are waiting.
+/// The shortcuts are collected again every `refresh_interval` and when a refresh is asked for
+fn spawn_provider_worker(
+  index: usize,
+  provider: std::sync::Arc<dyn Provider>,
+  refresh_interval: Duration,
+  messages: std::sync::mpsc::Sender<ProviderMessage>
+) -> std::sync::mpsc::Sender<WorkerRequest> {
+  let (requests, incoming) = std::sync::mpsc::channel::<WorkerRequest>();
+  std::thread::spawn(move || {
+    let cached = shortcuts_cache_path(provider.as_ref())
+      .filter(|_| provider.cached())
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|content| serde_json::from_str::<Vec<Shortcut>>(&content).ok());
+    if let Some(mut cached) = cached {
+      mark_missing(&mut cached);
+      if messages.send(ProviderMessage::Shortcuts(index, cached)).is_err() {
+        return;
+      }
+    }
+    if messages.send(ProviderMessage::Shortcuts(index, collect_shortcuts(provider.as_ref()))).is_err() {
+      return;
+    }
+    loop {
+      let (mut search, mut refresh) = match incoming.recv_timeout(refresh_interval) {
+        Ok(WorkerRequest::Search(id, query)) => (Some((id, query)), false),
+        Ok(WorkerRequest::Refresh) | Err(std::sync::mpsc::RecvTimeoutError::Timeout) => (None, true),
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+      };
+      while let Ok(request) = incoming.try_recv() {
+        match request {
+          WorkerRequest::Search(id, query) => search = Some((id, query)),
+          WorkerRequest::Refresh => refresh = true,
+        }
+      }
+      let mut sent = Ok(());
+      if refresh {
+        sent = messages.send(ProviderMessage::Shortcuts(index, collect_shortcuts(provider.as_ref())));
+      }
+      if let Some((id, query)) = search {
+        sent = sent.and(messages.send(ProviderMessage::Results(id, provider.search(&query))));
+      }
+      if sent.is_err() {
+        return;
+      }
+    }
+  });
+  requests
+}
+
+/// Everything that changes the app, fed to `App::update` one after another. Keys are turned into
+/// messages by `App::message`, so the app can be driven without a terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+  /// Leaves without opening anything, also out of a popup
+  Cancel,
+  SelectPrevious,
+  SelectNext,
+  /// Opens the highlighted match, or collapses or expands the highlighted category
+  OpenSelected,
+  /// Hides the shortcuts of the category of the highlighted row
+  Collapse,
+  /// Shows the shortcuts of the highlighted category again
+  Expand,
+  /// Copies the resolved path or URL of the highlighted match instead of opening it
+  CopySelected,
+  /// Shows the highlighted File or Dir in the file manager instead of opening it
+  RevealSelected,
+  /// Stars the highlighted match, or unstars it
+  ToggleFavorite,
+  /// Shows what else can be done with the highlighted match
+  OpenActions,
+  RefreshProviders,
+  /// A key edited into the search, which is matched again
+  Input(KeyEvent),
+  /// The answer to `pending_confirmation`
+  Confirm(bool),
+  /// A key while `failed_open` offers other ways to open the shortcut
+  FailedOpenKey(KeyEvent),
+  /// A key typed into the answer of `snippet_prompt`
+  PromptKey(KeyEvent),
+  /// A key while `action_menu` is open
+  ActionKey(KeyEvent),
+  /// Shows the matches of the next tab, kind by kind after all of them
+  NextTab,
+  PreviousTab,
+  /// Shows the matches of a kind, or all of them, as a click on its tab does
+  SelectTab(Option<ShortcutKind>),
+  /// Labels the rows, a row is opened by typing its label
+  StartHints,
+  /// A key typed into the label of `hints`
+  HintKey(KeyEvent),
+  /// Lists the last launches over the matches, or hides them again
+  ToggleLaunchLog,
+  /// A key while `launch_log` is open
+  LaunchLogKey(KeyEvent),
+  /// Another bullet was started while this one is open
+  Summoned(Summon),
+  /// Time passed, what the workers and other bullets sent meanwhile is taken in
+  Tick,
+}
+
+pub struct App {
+  pub config: Result<Config, LoadConfigError>,
+  pub providers: Vec<std::sync::Arc<dyn Provider>>,
+  /// Shortcuts of each provider, `None` until its worker has collected them
+  provided: Vec<Option<Vec<SharedShortcut>>>,
+  /// Request senders of the provider workers
+  workers: Vec<std::sync::mpsc::Sender<WorkerRequest>>,
+  messages: std::sync::mpsc::Receiver<ProviderMessage>,
+  /// Id of the current query, results of older ones are dropped
+  search_id: u64,
+  /// Workers that have not answered the current query yet
+  awaiting_results: usize,
+  /// Provided shortcuts matching the query
+  found: Vec<SharedShortcut>,
+  /// Results the providers made for the query so far
+  generated: Vec<SharedShortcut>,
+  /// Matches of the search, ordered by kind as they are listed
+  pub matched_shortcuts: Vec<SharedShortcut>,
+  /// Rows of the list: the matches, grouped under the headings of their categories while the
+  /// query is empty
+  pub entries: Vec<Entry>,
+  /// Categories whose shortcuts are hidden under their heading
+  collapsed: HashSet<String>,
+  /// Kind of the tab whose matches are listed, all of them are when `None`
+  pub tab: Option<ShortcutKind>,
+  /// Starred shortcuts, listed first
+  pub favorites: Favorites,
+  /// The query the matches are for
+  pub(crate) search: String,
+  /// When each seq was last opened, only loaded with the `last_used` column or `last_used` order
+  pub last_used: Option<HashMap<String, i64>>,
+  /// Seqs of the last launched shortcuts, newest first, listed under `Entry::RECENT` while the query
+  /// is empty. Only loaded with the config's `recent`
+  pub recent: Vec<String>,
+  /// How many times each seq was opened, only loaded with the `launch_count` column
+  pub launch_counts: Option<HashMap<String, i64>>,
+  /// Index of the highlighted row of `entries`, opened with Enter
+  pub selected: usize,
+  /// Width of the aliases column for `entries`, worked out when they are listed rather than on
+  /// every draw
+  pub aliases_width: u16,
+  /// Whether a match is missing or a favorite, only then the table has a column for their marks
+  pub marked: bool,
+  /// Index of the first of `entries` in view, only moved when the selection leaves the view
+  pub offset: usize,
+  /// Number of `entries` the view has room for, set when it is drawn
+  pub rows_in_view: usize,
+  /// Shortcut with `confirm` waiting for a yes before it is opened, or one waiting for it before it
+  /// is deleted from the config
+  pub pending_confirmation: Option<Confirmation>,
+  /// Shortcut the default application failed to open, waiting for the user to pick another way
+  pub failed_open: Option<FailedOpen>,
+  /// Snippet asking for the values of its `{?name}` placeholders before it is copied
+  pub snippet_prompt: Option<SnippetPrompt>,
+  /// Actions offered for the highlighted match
+  pub action_menu: Option<ActionMenu>,
+  /// What is typed of a row's label while the rows are labelled, see `hint_label`
+  pub hints: Option<String>,
+  /// The last launches from the history, listed over the matches while open
+  pub launch_log: Option<LaunchLog>,
+  /// Arguments added to those of the opened shortcut
+  extra_args: Vec<String>,
+  /// Only shortcuts of this kind are searched when set
+  kind_filter: Option<ShortcutKind>,
+  /// Opens the shortcuts, `SystemLauncher` unless swapped with `with_launcher`
+  launcher: std::sync::Arc<dyn Launcher>,
+  /// Short note of what a key did, shown for `Toast::DURATION`
+  pub toast: Option<Toast>,
+  /// Set when this is the only picker, other bullets summon it then
+  pub instance: Option<Instance<Summon>>,
+  /// Script of `--replay` the ui takes its keys from until it runs out
+  pub replay: Option<Replay>,
+  /// The search field, its first line is the query
+  pub search_input: TextArea<'static>,
+  /// Whether anything the ui shows changed since it last drew, it only draws then
+  pub dirty: bool,
+  pub running: bool,
+  /// Whether the user left without opening anything
+  pub cancelled: bool,
+}
+
+impl App {
+  /// Loads the config and starts the provider workers. Their shortcuts stream in afterwards,
+  /// `wait_for_shortcuts` blocks until all of them are there
+  pub fn new(kind_filter: Option<ShortcutKind>) -> Self {
+    App::with_config(Config::load(), kind_filter)
+  }
+  /// Starts the provider workers of an already loaded config, or shows why it didn't load
+  pub fn with_config(config: Result<Config, LoadConfigError>, kind_filter: Option<ShortcutKind>) -> Self {
+    let (sender, messages) = std::sync::mpsc::channel();
+    let terminal = config.as_ref().map(|config| config.terminal.clone()).unwrap_or_default();
+    let mut app = App {
+      running: true,
+      dirty: true,
+      cancelled: false,
+      search_input: TextArea::default(),
+      config,
+      providers: vec![],
+      provided: vec![],
+      workers: vec![],
+      messages,
+      search_id: 0,
+      awaiting_results: 0,
+      found: vec![],
+      generated: vec![],
+      matched_shortcuts: vec![],
+      entries: vec![],
+      collapsed: HashSet::new(),
+      tab: None,
+      favorites: Favorites::load(),
+      search: String::new(),
+      last_used: None,
+      recent: vec![],
+      launch_counts: None,
+      selected: 0,
+      aliases_width: 0,
+      marked: false,
+      offset: 0,
+      rows_in_view: 0,
+      pending_confirmation: None,
+      failed_open: None,
+      snippet_prompt: None,
+      action_menu: None,
+      hints: None,
+      launch_log: None,
+      extra_args: vec![],
+      kind_filter,
+      launcher: std::sync::Arc::new(SystemLauncher::new(terminal)),
+      instance: None,
+      replay: None,
+      toast: None,
+    };
+    if let Ok(config) = &app.config {
+      app.providers = build_providers(config).into_iter().map(std::sync::Arc::from).collect();
+      app.provided = vec![None; app.providers.len()];
+      if config.columns.last_used || config.order == Order::LastUsed {
+        let last_used = History::open().and_then(|history| history.last_used());
+        app.last_used = Some(last_used.unwrap_or_else(|e| {
+          error!("failed to read the history for the last used column: {e}");
+          HashMap::new()
+        }));
+      }
+      if config.recent > 0 {
+        let recent = History::open().and_then(|history| history.recently_used(config.recent));
+        app.recent = recent.unwrap_or_else(|e| {
+          error!("failed to read the history for the recently launched shortcuts: {e}");
+          vec![]
+        });
+      }
+      if config.columns.launch_count {
+        let launch_counts = History::open().and_then(|history| history.launch_counts());
+        app.launch_counts = Some(launch_counts.unwrap_or_else(|e| {
+          error!("failed to read the history for the launch counts: {e}");
+          HashMap::new()
+        }));
+      }
+      let refresh_interval = Duration::from_secs(config.providers.refresh_interval.max(1));
+      app.workers = app.providers
+        .iter()
+        .enumerate()
+        .map(|(index, provider)| {
+          spawn_provider_worker(index, provider.clone(), refresh_interval, sender.clone())
+        })
+        .collect();
+    }
+    app
+  }
+  /// Opens shortcuts through `launcher` instead of for real
+  pub fn with_launcher(mut self, launcher: std::sync::Arc<dyn Launcher>) -> Self {
+    self.launcher = launcher;
+    self
+  }
+  /// Passes `args` to the app or terminal command of the opened shortcut after its own, for `bullet run`
+  pub fn with_extra_args(mut self, args: Vec<String>) -> Self {
+    self.extra_args = args;
+    self
+  }
+  /// Keeps the starred shortcuts in `favorites` instead of the data dir
+  pub fn with_favorites(mut self, favorites: Favorites) -> Self {
+    self.favorites = favorites;
+    self.list_matches();
+    self
+  }
+  /// Listens for the other bullets started while this one is open
+  pub fn with_instance(mut self, instance: Instance<Summon>) -> Self {
+    self.instance = Some(instance);
+    self
+  }
+  /// Takes the keys from a script before the keyboard
+  pub fn with_replay(mut self, replay: Replay) -> Self {
+    self.replay = Some(replay);
+    self
+  }
+  /// Provided shortcuts of providers without a prefix, restricted to `kind_filter`
+  pub fn shortcuts(&self) -> Vec<SharedShortcut> {
+    let shortcuts: Vec<SharedShortcut> = self.providers
+      .iter()
+      .zip(&self.provided)
+      .filter(|(provider, _)| provider.prefix().is_none())
+      .flat_map(|(_, shortcuts)| shortcuts.iter().flatten().cloned())
+      .collect();
+    self.filter_kind(&shortcuts)
+  }
+  /// Whether shortcuts or results of the query are still coming from the workers
+  pub fn loading(&self) -> bool {
+    self.awaiting_results > 0 || self.provided.iter().any(Option::is_none)
+  }
+  pub fn wait_for_shortcuts(&mut self) {
+    while self.provided.iter().any(Option::is_none) {
+      match self.messages.recv() {
+        Ok(message) => self.handle_message(message),
+        Err(_) => break,
+      }
+    }
+  }
+  /// Takes in what the workers sent since the last call
+  pub fn receive(&mut self) {
+    while let Ok(message) = self.messages.try_recv() {
+      self.handle_message(message);
+    }
+  }
+  fn handle_message(&mut self, message: ProviderMessage) {
+    match message {
+      ProviderMessage::Shortcuts(index, shortcuts) => {
+        let loading = self.provided.iter().any(Option::is_none);
+        self.provided[index] = Some(shortcuts.into_iter().map(SharedShortcut::new).collect());
+        let (shortcuts, scoped_search) = self.scope(&self.search);
+        self.found = shortcuts.find(scoped_search.to_string());
+        if loading && !self.search.is_empty() && self.provided.iter().all(Option::is_some) {
+          // what was typed while loading is handled like it was typed now, narrowing the fresh matches
+          let result = self.find_and_handle_matches(self.search.clone());
+          self.toast_failure(result);
+          return;
+        }
+      }
+      ProviderMessage::Results(id, results) if id == self.search_id => {
+        self.awaiting_results = self.awaiting_results.saturating_sub(1);
+        let results: Vec<SharedShortcut> = results.into_iter().map(SharedShortcut::new).collect();
+        self.generated.extend(self.filter_kind(&results));
+      }
+      ProviderMessage::Results(..) => return,
+    }
+    self.list_matches();
+    self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+  }
+  /// Has every provider collect its shortcuts again
+  pub fn refresh_providers(&self) {
+    info!("refreshing providers");
+    for worker in &self.workers {
+      if worker.send(WorkerRequest::Refresh).is_err() {
+        error!("a provider worker is gone, its shortcuts stay as they are");
+      }
+    }
+  }
+  fn list_matches(&mut self) {
+    self.matched_shortcuts = self.found.clone();
+    self.matched_shortcuts.extend(self.generated.iter().cloned());
+    match self.config.as_ref().map(|config| config.order) {
+      Ok(Order::LastUsed) => {
+        let last_used = |s: &SharedShortcut| self.last_used.as_ref()?.get(s.seq.first()?).copied();
+        self.matched_shortcuts.sort_by_key(|s| (!self.favorites.contains(s), std::cmp::Reverse(last_used(s))));
+      }
+      _ => self.matched_shortcuts.sort_by_key(|s| (!self.favorites.contains(s), s.kind)),
+    }
+    self.entries = self.list_entries();
+    self.aliases_width = crate::ui::aliases_width(self);
+    self.marked = self.matched_shortcuts.iter().any(|s| s.missing || self.favorites.contains(s));
+    self.dirty = true;
+  }
+  /// The matches as rows. With an empty query, recently launched shortcuts, favorites or shortcuts
+  /// that have a category, they are listed under headings: `Entry::RECENT` first, listing again
+  /// what the headings after it list, then `Entry::FAVORITES`, the categories by name and the rest
+  /// under `Entry::OTHER` last
+  fn list_entries(&self) -> Vec<Entry> {
+    let matched: Vec<&SharedShortcut> = self.matched_shortcuts
+      .iter()
+      .filter(|s| self.tab.is_none_or(|kind| s.kind == kind))
+      .collect();
+    let recent: Vec<SharedShortcut> = self.recent
+      .iter()
+      .filter_map(|seq| matched.iter().find(|s| s.seq.first() == Some(seq)).map(|s| (*s).clone()))
+      .collect();
+    let grouped = !recent.is_empty() ||
+      matched.iter().any(|s| s.category.is_some() || self.favorites.contains(s));
+    if !self.search.is_empty() || !grouped {
+      return matched.into_iter().cloned().map(Entry::Shortcut).collect();
+    }
+    let mut favorites = vec![];
+    let mut categories: BTreeMap<String, Vec<SharedShortcut>> = BTreeMap::new();
+    let mut other = vec![];
+    for s in matched {
+      match &s.category {
+        _ if self.favorites.contains(s) => favorites.push(s.clone()),
+        Some(category) => categories.entry(category.clone()).or_default().push(s.clone()),
+        None => other.push(s.clone()),
+      }
+    }
+    let recent = (!recent.is_empty()).then(|| (Entry::RECENT.to_string(), recent));
+    let favorites = (!favorites.is_empty()).then(|| (Entry::FAVORITES.to_string(), favorites));
+    let other = (!other.is_empty()).then(|| (Entry::OTHER.to_string(), other));
+    let mut entries = vec![];
+    for (name, shortcuts) in recent.into_iter().chain(favorites).chain(categories).chain(other) {
+      let collapsed = self.collapsed.contains(&name);
+      entries.push(Entry::Category { name, len: shortcuts.len(), collapsed });
+      if !collapsed {
+        entries.extend(shortcuts.into_iter().map(Entry::Shortcut));
+      }
+    }
+    entries
+  }
+  /// The tabs over the matches with how many matches each has: all of them first, then one per kind
+  /// that has matches, or is the open tab, in the order they are listed
+  pub fn tabs(&self) -> Vec<(Option<ShortcutKind>, usize)> {
+    let mut counts: BTreeMap<ShortcutKind, usize> = BTreeMap::new();
+    for s in &self.matched_shortcuts {
+      *counts.entry(s.kind).or_default() += 1;
+    }
+    if let Some(tab) = self.tab {
+      counts.entry(tab).or_default();
+    }
+    std::iter::once((None, self.matched_shortcuts.len()))
+      .chain(counts.into_iter().map(|(kind, count)| (Some(kind), count)))
+      .collect()
+  }
+  /// Lists the matches of another tab, highlighting its first row
+  fn select_tab(&mut self, tab: Option<ShortcutKind>) {
+    self.tab = tab;
+    self.selected = 0;
+    self.entries = self.list_entries();
+    self.aliases_width = crate::ui::aliases_width(self);
+    self.marked = self.matched_shortcuts.iter().any(|s| s.missing || self.favorites.contains(s));
+  }
+  /// The tab `step` tabs after the open one, wrapping around
+  fn cycle_tab(&mut self, step: isize) {
+    let tabs = self.tabs();
+    let open = tabs.iter().position(|(kind, _)| *kind == self.tab).unwrap_or(0) as isize;
+    let next = (open + step).rem_euclid(tabs.len() as isize) as usize;
+    self.select_tab(tabs[next].0);
+  }
+  /// The shortcut of the highlighted row, `None` on a category heading
+  pub fn selected_shortcut(&self) -> Option<SharedShortcut> {
+    match self.entries.get(self.selected) {
+      Some(Entry::Shortcut(shortcut)) => Some(shortcut.clone()),
+      _ => None,
+    }
+  }
+  /// Category of the highlighted row, the heading it is listed under
+  fn selected_category(&self) -> Option<String> {
+    self.entries
+      .get(..=self.selected)?
+      .iter()
+      .rev()
+      .find_map(|entry| match entry {
+        Entry::Category { name, .. } => Some(name.clone()),
+        Entry::Shortcut(_) => None,
+      })
+  }
+  /// Hides or shows the shortcuts of a category, highlighting its heading
+  fn collapse(&mut self, category: String, collapsed: bool) {
+    if collapsed {
+      self.collapsed.insert(category.clone());
+    } else {
+      self.collapsed.remove(&category);
+    }
+    self.list_matches();
+    self.selected = self.entries
+      .iter()
+      .position(|entry| matches!(entry, Entry::Category { name, .. } if *name == category))
+      .unwrap_or(0);
+  }
+  fn filter_kind(&self, shortcuts: &[SharedShortcut]) -> Vec<SharedShortcut> {
+    shortcuts
+      .iter()
+      .filter(|s| self.kind_filter.is_none_or(|kind| s.kind == kind))
+      .cloned()
+      .collect()
+  }
+  /// Shortcuts a search applies to, along with the search stripped of the provider prefix
+  fn scope<'a>(&self, search: &'a str) -> (Vec<SharedShortcut>, &'a str) {
+    match self.scope_index(search) {
+      (Some(index), rest) => (self.filter_kind(self.provided[index].as_deref().unwrap_or_default()), rest),
+      (None, rest) => (self.shortcuts(), rest),
+    }
+  }
+  /// Index of the provider whose prefix the search starts with, along with the search stripped of it
+  pub(crate) fn scope_index<'a>(&self, search: &'a str) -> (Option<usize>, &'a str) {
+    for (index, provider) in self.providers.iter().enumerate() {
+      if let Some(rest) = provider.prefix().and_then(|prefix| search.strip_prefix(prefix)) {
+        return (Some(index), rest);
+      }
+    }
+    (None, search)
+  }
+  /// Returns the only matched shortcut, or the one whose seq equals `search` exactly
+  pub fn resolve_match(matched: &[SharedShortcut], search: &str) -> Result<SharedShortcut, BulletError> {
+    if matched.len() == 1 {
+      return Ok(matched[0].clone());
+    }
+    matched
+      .iter()
+      .find(|s| s.seq.iter().any(|seq| fold(seq) == fold(search)))
+      .cloned()
+      .ok_or_else(|| {
+        match matched.len() {
+          0 => BulletError::NoMatch(search.to_string()),
+          count => BulletError::AmbiguousMatch(search.to_string(), count),
+        }
+      })
+  }
+  /// Lists the provided shortcuts matching `search` and returns them.
+  /// The workers are asked for their results, which are added as they come in
+  pub fn update_matches(&mut self, search: &str) -> Vec<SharedShortcut> {
+    let (scope, scoped_search) = self.scope_index(search);
+    // typing on only narrows the matches down, so the previous ones are enough to search
+    let narrowing = fold(search).starts_with(&fold(&self.search)) &&
+      scope == self.scope_index(&self.search).0 &&
+      self.provided.iter().all(Option::is_some);
+    let found = if narrowing {
+      self.found.find(scoped_search.to_string())
+    } else {
+      self.scope(search).0.find(scoped_search.to_string())
+    };
+    debug!(search = scoped_search, matched = found.len(), "searched shortcuts");
+    self.search_id += 1;
+    self.awaiting_results = 0;
+    for worker in &self.workers {
+      if worker.send(WorkerRequest::Search(self.search_id, search.to_string())).is_ok() {
+        self.awaiting_results += 1;
+      }
+    }
+    self.found = found.clone();
+    self.generated.clear();
+    self.search = search.to_string();
+    self.list_matches();
+    found
+  }
+  pub fn find_and_handle_matches(&mut self, search: String) -> Result<(), BulletError> {
+    let found = self.update_matches(&search);
+    self.selected = 0;
+    if self.provided.iter().any(Option::is_none) {
+      // a match is only opened right away once every provider is in, it may not be unique otherwise
+      return Ok(());
+    }
+    // a search URL, template or note is opened once a query is typed after it, through its generated result
+    let found: Vec<SharedShortcut> = found
+      .into_iter()
+      .filter(|s| !s.is_search() && !s.is_template() && s.kind != ShortcutKind::Note)
+      .collect();
+    let shortcut = App::resolve_match(&found, self.scope_index(&search).1)?;
+    self.open(&shortcut)
+  }
+  /// Opens the match whose seq is `search` once the providers answered it, like the search, template
+  /// or note typed as `<seq> <query>` that `find_and_handle_matches` leaves to its generated result
+  pub fn open_result(&mut self, search: &str) -> Result<(), BulletError> {
+    while self.awaiting_results > 0 {
+      match self.messages.recv() {
+        Ok(message) => self.handle_message(message),
+        Err(_) => break,
+      }
+    }
+    let shortcut = self.matched_shortcuts
+      .iter()
+      .find(|s| s.seq.iter().any(|seq| fold(seq) == fold(search)))
+      .cloned()
+      .ok_or(BulletError::NoMatch(search.to_string()))?;
+    self.open(&shortcut)
+  }
+  /// Opens the shortcut a `bullet://` link names. Nobody is there to confirm, so shortcuts with
+  /// `confirm` are not opened, a web page could otherwise run them. Nor are snippets that ask for values
+  pub fn open_linked(&mut self, seq: &str) -> Result<(), BulletError> {
+    let result = self.find_and_handle_matches(seq.to_string());
+    self.failed_open = None;
+    if let Some(pending) = self.pending_confirmation.take() {
+      error!(seq = pending.shortcut().name(), "not opening a shortcut that asks for confirmation from a link");
+      return Err(BulletError::Cancelled);
+    }
+    if let Some(prompt) = self.snippet_prompt.take() {
+      error!(seq = prompt.shortcut.name(), "not opening a snippet that asks for values from a link");
+      return Err(BulletError::Cancelled);
+    }
+    result
+  }
+  /// Lists the matches again when a provider has new results in the background, keeping the selection
+  pub fn refresh_matches(&mut self) {
+    if self.providers.iter().any(|p| p.updated()) {
+      let search = self.search.clone();
+      self.update_matches(&search);
+      self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+  }
+  pub fn open_selected(&mut self) -> Result<(), BulletError> {
+    match self.entries.get(self.selected).cloned() {
+      Some(Entry::Shortcut(shortcut)) => self.open(&shortcut),
+      Some(Entry::Category { name, collapsed, .. }) => {
+        self.collapse(name, !collapsed);
+        Ok(())
+      }
+      None => Err(BulletError::NoMatch(String::new())),
+    }
+  }
+  /// Opens a shortcut, or holds it back as `pending_confirmation` if it asks for confirmation
+  fn open(&mut self, shortcut: &Shortcut) -> Result<(), BulletError> {
+    if shortcut.confirm {
+      self.pending_confirmation = Some(Confirmation::Open(Box::new(shortcut.clone())));
+      return Ok(());
+    }
+    self.open_confirmed(shortcut)
+  }
+  /// Opens a confirmed shortcut, or holds a snippet back as `snippet_prompt` while its
+  /// `{?name}` placeholders are asked for
+  pub fn open_confirmed(&mut self, shortcut: &Shortcut) -> Result<(), BulletError> {
+    let names = shortcut.snippet_prompts();
+    if !names.is_empty() {
+      self.snippet_prompt = Some(SnippetPrompt {
+        shortcut: shortcut.clone(),
+        names,
+        answers: vec![],
+        input: String::new(),
+      });
+      return Ok(());
+    }
+    self.open_answered(shortcut)
+  }
+  /// Opens a shortcut with nothing left to ask for, the way `default_actions` says for its kind
+  pub fn open_answered(&mut self, shortcut: &Shortcut) -> Result<(), BulletError> {
+    // the arguments of `bullet run` are for opening it
+    let action = if self.extra_args.is_empty() { self.default_action(shortcut) } else { Action::Open };
+    self.launch_as(shortcut, action)
+  }
+  /// Does `action` with a shortcut, recording it in the history and closing bullet if it worked
+  fn launch_as(&mut self, shortcut: &Shortcut, action: Action) -> Result<(), BulletError> {
+    let mut shortcut = shortcut.clone();
+    if shortcut.kind == ShortcutKind::Url && shortcut.browser.is_none() {
+      shortcut.browser = self.config.as_ref().ok().and_then(|config| config.browser.clone());
+    }
+    if !self.extra_args.is_empty() {
+      if !matches!(shortcut.kind, ShortcutKind::App | ShortcutKind::Terminal) {
+        let e = std::io::Error::new(
+          std::io::ErrorKind::InvalidInput,
+          "only app and terminal shortcuts take arguments"
+        );
+        return Err(BulletError::LaunchFailed(shortcut.name().to_string(), e));
+      }
+      shortcut.args.extend(self.extra_args.iter().cloned());
+    }
+    let shortcut = &shortcut;
+    let path = shortcut.get_prefixed_path();
+    info!(seq = shortcut.name(), path, ?action, "launching");
+    let prefixed = shortcut.prefixed_path();
+    let result = match action {
+      Action::CopyPath => self.launcher.copy(&path),
+      Action::Reveal => self.launcher.reveal(&prefixed),
+      Action::Terminal if shortcut.kind == ShortcutKind::Dir => self.launcher.open_terminal(&prefixed),
+      Action::Terminal => self.launcher.open_terminal(prefixed.parent().unwrap_or(Path::new("."))),
+      Action::Editor => self.launcher.edit(&prefixed),
+      _ if shortcut.kind == ShortcutKind::Session => self.launch_session(shortcut),
+      _ => self.launcher.launch(shortcut),
+    };
+    let launched = Launch {
+      seq: shortcut.name().to_string(),
+      path: path.clone(),
+      kind: shortcut.kind,
+      query: self.search.clone(),
+      launched_at: chrono::Utc::now().timestamp(),
+      error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    if self.launcher.records_history() {
+      if let Err(e) = History::open().and_then(|history| history.record(&launched)) {
+        error!("failed to record the launch in the history: {e}");
+      }
+    }
+    if let (Err(e), Action::Open, true) = (&result, action, shortcut.opens_with_default_app()) {
+      self.failed_open = Some(FailedOpen {
+        shortcut: shortcut.clone(),
+        error: e.to_string(),
+        application: None,
+      });
+    }
+    result.map_err(|e| {
+      error!(seq = shortcut.name(), path, "launch failed: {e}");
+      BulletError::LaunchFailed(shortcut.name().to_string(), e)
+    })?;
+    self.running = false;
+    Ok(())
+  }
+  /// Opens the items of a Session in order, each after its delay, going on past the ones that fail.
+  /// The error tells how each item went when any failed
+  fn launch_session(&self, session: &Shortcut) -> std::io::Result<()> {
+    let browser = self.config.as_ref().ok().and_then(|config| config.browser.clone());
+    let mut statuses = vec![];
+    let mut failed = 0;
+    for item in &session.items {
+      std::thread::sleep(Duration::from_millis(item.delay.unwrap_or(0)));
+      let mut shortcut = item.shortcut();
+      if shortcut.kind == ShortcutKind::Url {
+        shortcut.browser = browser.clone();
+      }
+      let result = match item.kind {
+        ShortcutKind::App | ShortcutKind::Dir | ShortcutKind::File | ShortcutKind::Url | ShortcutKind::Terminal => {
+          self.launcher.launch(&shortcut)
+        }
+        kind => {
+          let kind = kind.to_possible_value().unwrap();
+          let e = format!("sessions can't open {} items", kind.get_name());
+          Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+        }
+      };
+      match result {
+        Ok(()) => {
+          info!(session = session.name(), item = item.path, "opened session item");
+          statuses.push(format!("{}: opened", item.path));
+        }
+        Err(e) => {
+          error!(session = session.name(), item = item.path, "failed to open session item: {e}");
+          statuses.push(format!("{}: {e}", item.path));
+          failed += 1;
+        }
+      }
+    }
+    if failed == 0 {
+      return Ok(());
+    }
+    Err(std::io::Error::other(format!("{failed} of {} items failed ({})", session.items.len(), statuses.join(", "))))
+  }
+  /// The message a key stands for, depending on the popup that is open
+  pub fn message(&self, key_event: KeyEvent) -> Message {
+    if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+      return Message::Cancel;
+    }
+    if self.failed_open.is_some() {
+      return Message::FailedOpenKey(key_event);
+    }
+    if self.snippet_prompt.is_some() {
+      return Message::PromptKey(key_event);
+    }
+    if self.action_menu.is_some() {
+      return Message::ActionKey(key_event);
+    }
+    if self.hints.is_some() {
+      return Message::HintKey(key_event);
+    }
+    if self.launch_log.is_some() {
+      return Message::LaunchLogKey(key_event);
+    }
+    if self.pending_confirmation.is_some() {
+      return Message::Confirm(key_event.code == KeyCode::Char('y'));
+    }
+    match key_event.code {
+      KeyCode::Esc => Message::Cancel,
+      KeyCode::Up => Message::SelectPrevious,
+      KeyCode::Down => Message::SelectNext,
+      KeyCode::Tab => Message::NextTab,
+      KeyCode::BackTab => Message::PreviousTab,
+      // Shift+Enter only reaches bullet in terminals that report modifiers of Enter
+      KeyCode::Char('k') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::OpenActions,
+      KeyCode::Enter if key_event.modifiers.contains(KeyModifiers::SHIFT) => Message::OpenActions,
+      KeyCode::Enter => Message::OpenSelected,
+      // the cursor has nowhere to go in an empty search
+      KeyCode::Left if self.search.is_empty() => Message::Collapse,
+      KeyCode::Right if self.search.is_empty() => Message::Expand,
+      KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+        Message::RefreshProviders
+      }
+      KeyCode::Char('y') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::CopySelected,
+      KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::RevealSelected,
+      KeyCode::Char('f') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::ToggleFavorite,
+      KeyCode::Char('g') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::StartHints,
+      KeyCode::Char('l') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Message::ToggleLaunchLog,
+      _ => Message::Input(key_event),
+    }
+  }
+  /// Applies a message to the app, the ui only draws what comes out of it
+  pub fn update(&mut self, message: Message) {
+    // only ticks can leave everything as it was
+    if message != Message::Tick {
+      self.dirty = true;
+    }
+    match message {
+      Message::Cancel => {
+        self.running = false;
+        self.cancelled = true;
+      }
+      Message::SelectPrevious => {
+        self.selected = self.selected.saturating_sub(1);
+      }
+      Message::SelectNext => {
+        self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+      }
+      Message::OpenSelected => {
+        let result = self.open_selected();
+        self.toast_failure(result);
+      }
+      Message::Collapse => {
+        if let Some(category) = self.selected_category() {
+          self.collapse(category, true);
+        }
+      }
+      Message::Expand => {
+        if let Some(Entry::Category { name, .. }) = self.entries.get(self.selected).cloned() {
+          self.collapse(name, false);
+        }
+      }
+      Message::CopySelected => {
+        if let Some(shortcut) = self.selected_shortcut() {
+          self.copy_path(&shortcut);
+        }
+      }
+      Message::RevealSelected => {
+        if let Some(shortcut) = self.selected_shortcut() {
+          self.reveal(&shortcut);
+        }
+      }
+      Message::ToggleFavorite => {
+        if let Some(shortcut) = self.selected_shortcut() {
+          self.toggle_favorite(&shortcut);
+        }
+      }
+      Message::OpenActions => {
+        if let Some(shortcut) = self.selected_shortcut() {
+          let actions = self.actions(&shortcut);
+          self.action_menu = Some(ActionMenu { shortcut, actions, selected: 0, application: None });
+        }
+      }
+      Message::ActionKey(key_event) => self.handle_action_key(key_event),
+      Message::NextTab => self.cycle_tab(1),
+      Message::PreviousTab => self.cycle_tab(-1),
+      Message::SelectTab(tab) => self.select_tab(tab),
+      Message::StartHints => self.hints = Some(String::new()),
+      Message::HintKey(key_event) => self.handle_hint_key(key_event),
+      Message::ToggleLaunchLog => self.toggle_launch_log(),
+      Message::LaunchLogKey(key_event) => self.handle_launch_log_key(key_event),
+      Message::RefreshProviders => self.refresh_providers(),
+      Message::Input(key_event) => {
+        self.search_input.input(key_event);
+        let search = self.search_input.lines()[0].clone();
+        let result = self.find_and_handle_matches(search);
+        self.toast_failure(result);
+      }
+      Message::Summoned(summon) => {
+        if let Err(e) = raise_terminal_window() {
+          debug!("failed to raise the terminal window: {e}");
+        }
+        if let Some(query) = summon.query {
+          self.set_search(&query);
+        }
+      }
+      Message::Confirm(confirmed) => {
+        match self.pending_confirmation.take() {
+          Some(Confirmation::Open(shortcut)) if confirmed => {
+            let result = self.open_confirmed(&shortcut);
+            self.toast_failure(result);
+          }
+          Some(Confirmation::Delete(shortcut)) if confirmed => self.delete(&shortcut),
+          _ => {}
+        }
+      }
+      Message::FailedOpenKey(key_event) => self.handle_failed_open_key(key_event),
+      Message::PromptKey(key_event) => self.handle_prompt_key(key_event),
+      Message::Tick => {
+        if self.toast.as_ref().is_some_and(|toast| toast.shown_at.elapsed() > Toast::DURATION) {
+          self.toast = None;
+          self.dirty = true;
+        }
+        self.receive();
+        self.refresh_matches();
+        for summon in self.instance.iter().flat_map(Instance::requests).collect::<Vec<_>>() {
+          self.update(Message::Summoned(summon));
+        }
+      }
+    }
+  }
+  /// Replaces what is typed in the search and matches it
+  pub fn set_search(&mut self, search: &str) {
+    self.search_input = TextArea::from([search]);
+    self.search_input.move_cursor(tui_textarea::CursorMove::End);
+    let result = self.find_and_handle_matches(search.to_string());
+    self.toast_failure(result);
+  }
+  /// Tells in a toast why opening a shortcut from the picker failed. Typing what matches nothing or
+  /// several shortcuts is no failure, nor is one `failed_open` offers other ways out of
+  fn toast_failure(&mut self, result: Result<(), BulletError>) {
+    match result {
+      Ok(()) | Err(BulletError::NoMatch(_) | BulletError::AmbiguousMatch(..)) => {}
+      Err(_) if self.failed_open.is_some() => {}
+      Err(e) => self.toast = Some(Toast::new(e.to_string(), true)),
+    }
+  }
+  /// Indices of the `entries` in view, the ones labelled by `hint_label`
+  pub fn in_view(&self) -> Range<usize> {
+    self.offset.min(self.entries.len())..(self.offset + self.rows_in_view).min(self.entries.len())
+  }
+  /// Label of the `index`th row in view while the rows are labelled, two of `HINT_KEYS`
+  pub fn hint_label(index: usize) -> String {
+    let keys = HINT_KEYS.as_bytes();
+    let index = index % (keys.len() * keys.len());
+    format!("{}{}", keys[index / keys.len()] as char, keys[index % keys.len()] as char)
+  }
+  /// Handles a key while the rows are labelled, opening the row in view of the label once both of
+  /// its letters are typed, whichever row is highlighted
+  fn handle_hint_key(&mut self, key_event: KeyEvent) {
+    let Some(typed) = self.hints.as_mut() else {
+      return;
+    };
+    match key_event.code {
+      KeyCode::Char(c) if HINT_KEYS.contains(c) => typed.push(c),
+      KeyCode::Backspace => {
+        typed.pop();
+        return;
+      }
+      _ => {
+        self.hints = None;
+        return;
+      }
+    }
+    if typed.len() < 2 {
+      return;
+    }
+    let typed = self.hints.take().unwrap_or_default();
+    let row = self
+      .in_view()
+      .enumerate()
+      .find(|(row, _)| App::hint_label(*row) == typed)
+      .map(|(_, index)| self.entries[index].clone());
+    match row {
+      Some(Entry::Shortcut(shortcut)) => {
+        let result = self.open(&shortcut);
+        self.toast_failure(result);
+      }
+      Some(Entry::Category { name, collapsed, .. }) => self.collapse(name, !collapsed),
+      None => {}
+    }
+  }
+  /// Handles a key while `failed_open` offers other ways to open the shortcut
+  pub fn handle_failed_open_key(&mut self, key_event: KeyEvent) {
+    let Some(failed) = self.failed_open.as_mut() else {
+      return;
+    };
+    let path = failed.shortcut.prefixed_path();
+    if let Some(application) = failed.application.as_mut() {
+      match key_event.code {
+        KeyCode::Esc => failed.application = None,
+        KeyCode::Backspace => {
+          application.pop();
+        }
+        KeyCode::Char(c) => application.push(c),
+        KeyCode::Enter if !application.trim().is_empty() => {
+          let application = application.trim().to_string();
+          info!(path = %path.display(), application, "opening with another application");
+          self.finish_failed_open(self.launcher.open_with(&path, &application));
+        }
+        _ => {}
+      }
+      return;
+    }
+    match key_event.code {
+      KeyCode::Char('r') => {
+        let shortcut = failed.shortcut.clone();
+        self.failed_open = None;
+        let result = self.open_answered(&shortcut);
+        self.toast_failure(result);
+      }
+      KeyCode::Char('f') if failed.shortcut.kind != ShortcutKind::Url => {
+        let folder = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        info!(path = %folder.display(), "opening the containing folder");
+        self.finish_failed_open(self.launcher.open(&folder));
+      }
+      KeyCode::Char('c') => {
+        self.finish_failed_open(self.launcher.copy(&path.to_string_lossy()));
+      }
+      KeyCode::Char('a') => failed.application = Some(String::new()),
+      KeyCode::Esc => self.failed_open = None,
+      _ => {}
+    }
+  }
+  /// Whether `shortcut` is one of the config's, which can be edited and deleted there
+  fn configured(&self, shortcut: &Shortcut) -> bool {
+    self.config
+      .as_ref()
+      .is_ok_and(|config| config.shortcuts.iter().any(|s| s.seq == shortcut.seq && s.path == shortcut.path))
+  }
+  /// What Enter does with a shortcut: what its `open` asks for, the config's `default_actions` for
+  /// its kind or opening it
+  pub fn default_action(&self, shortcut: &Shortcut) -> Action {
+    let kind = shortcut.kind;
+    match shortcut.open {
+      Some(OpenTarget::File) if kind == ShortcutKind::File => return Action::Open,
+      Some(OpenTarget::Folder) if kind == ShortcutKind::File => return Action::Reveal,
+      _ => {}
+    }
+    self.config
+      .as_ref()
+      .ok()
+      .and_then(|config| config.default_actions.get(&kind).copied())
+      .filter(|action| action.applies_to(kind))
+      .unwrap_or(Action::Open)
+  }
+  /// What the action menu offers for `shortcut`, what Enter does first
+  fn actions(&self, shortcut: &Shortcut) -> Vec<Action> {
+    let default = self.default_action(shortcut);
+    let configured = self.configured(shortcut);
+    let others = Action::ALL.into_iter().filter(|action| {
+      *action != default &&
+        action.applies_to(shortcut.kind) &&
+        (configured || !matches!(action, Action::Edit | Action::Delete))
+    });
+    std::iter::once(default).chain(others).collect()
+  }
+  /// Reads the last launches from the history and lists them, or hides them when they are listed
+  fn toggle_launch_log(&mut self) {
+    if self.launch_log.take().is_some() {
+      return;
+    }
+    match History::open().and_then(|history| history.recent(LaunchLog::LIMIT)) {
+      Ok(launches) => self.launch_log = Some(LaunchLog { launches, offset: 0 }),
+      Err(e) => self.toast = Some(Toast::new(format!("Failed to read the history: {e}"), true)),
+    }
+  }
+  /// Scrolls the launch log with the arrows, Esc and Ctrl+L hide it
+  pub fn handle_launch_log_key(&mut self, key_event: KeyEvent) {
+    let Some(log) = self.launch_log.as_mut() else {
+      return;
+    };
+    match key_event.code {
+      KeyCode::Esc => self.launch_log = None,
+      KeyCode::Char('l') if key_event.modifiers.contains(KeyModifiers::CONTROL) => self.launch_log = None,
+      KeyCode::Up => log.offset = log.offset.saturating_sub(1),
+      KeyCode::Down => log.offset = (log.offset + 1).min(log.launches.len().saturating_sub(1)),
+      _ => {}
+    }
+  }
+  /// Copies the path of a shortcut
+  fn copy_path(&mut self, shortcut: &SharedShortcut) {
+    let path = shortcut.get_prefixed_path();
+    self.toast = Some(match self.launcher.copy(&path) {
+      Ok(()) => Toast::new(format!("Copied {path}"), false),
+      Err(e) => Toast::new(format!("Failed to copy: {e}"), true),
+    });
+  }
+  /// Shows a file or folder in the file manager
+  fn reveal(&mut self, shortcut: &SharedShortcut) {
+    if !matches!(shortcut.kind, ShortcutKind::File | ShortcutKind::Dir) {
+      self.toast = Some(Toast::new("Only files and folders can be revealed".to_string(), true));
+      return;
+    }
+    let path = shortcut.prefixed_path();
+    info!(path = %path.display(), "revealing in the file manager");
+    match self.launcher.reveal(&path) {
+      Ok(()) => self.running = false,
+      Err(e) => self.toast = Some(Toast::new(format!("Failed to reveal: {e}"), true)),
+    }
+  }
+  /// Stars or unstars a shortcut, keeping it highlighted as it moves along with its rank
+  fn toggle_favorite(&mut self, shortcut: &SharedShortcut) {
+    self.toast = Some(match self.favorites.toggle(shortcut) {
+      Ok(true) => Toast::new(format!("Starred {}", shortcut.name()), false),
+      Ok(false) => Toast::new(format!("Unstarred {}", shortcut.name()), false),
+      Err(e) => Toast::new(format!("Failed to save the favorites: {e}"), true),
+    });
+    self.list_matches();
+    self.selected = self.entries
+      .iter()
+      .position(|entry| matches!(entry, Entry::Shortcut(s) if SharedShortcut::ptr_eq(s, shortcut)))
+      .unwrap_or(0);
+  }
+  /// Handles a key while `action_menu` is open, picking an action with Enter or its key. The
+  /// actions are done on the shortcut the menu was opened on, whatever is highlighted meanwhile
+  pub fn handle_action_key(&mut self, key_event: KeyEvent) {
+    let Some(menu) = self.action_menu.as_mut() else {
+      return;
+    };
+    let shortcut = menu.shortcut.clone();
+    if let Some(application) = menu.application.as_mut() {
+      match key_event.code {
+        KeyCode::Esc => menu.application = None,
+        KeyCode::Backspace => {
+          application.pop();
+        }
+        KeyCode::Char(c) => application.push(c),
+        KeyCode::Enter if !application.trim().is_empty() => {
+          let application = application.trim().to_string();
+          let path = shortcut.prefixed_path();
+          info!(path = %path.display(), application, "opening with another application");
+          self.action_menu = None;
+          match self.launcher.open_with(&path, &application) {
+            Ok(()) => self.running = false,
+            Err(e) => self.toast = Some(Toast::new(format!("Failed to open with {application}: {e}"), true)),
+          }
+        }
+        _ => {}
+      }
+      return;
+    }
+    let action = match key_event.code {
+      KeyCode::Esc | KeyCode::Tab => {
+        self.action_menu = None;
+        return;
+      }
+      KeyCode::Up => {
+        menu.selected = menu.selected.saturating_sub(1);
+        return;
+      }
+      KeyCode::Down => {
+        menu.selected = (menu.selected + 1).min(menu.actions.len() - 1);
+        return;
+      }
+      KeyCode::Enter => menu.actions[menu.selected],
+      KeyCode::Char(c) => match menu.actions.iter().find(|action| action.key() == c) {
+        Some(action) => *action,
+        None => return,
+      },
+      _ => return,
+    };
+    self.action_menu = None;
+    match action {
+      Action::Open if self.default_action(&shortcut) == Action::Open => {
+        let result = self.open(&shortcut);
+        self.toast_failure(result);
+      }
+      Action::Open | Action::Terminal | Action::Editor => {
+        if let Err(e) = self.launch_as(&shortcut, action) {
+          self.toast = Some(Toast::new(e.to_string(), true));
+        }
+      }
+      Action::OpenWith => {
+        self.action_menu = Some(ActionMenu { shortcut, actions: vec![], selected: 0, application: Some(String::new()) });
+      }
+      Action::CopyPath => self.copy_path(&shortcut),
+      Action::Reveal => self.reveal(&shortcut),
+      Action::Pin => self.toggle_favorite(&shortcut),
+      Action::Edit => {
+        let path = Config::path().map_err(|e| std::io::Error::other(e.to_string()));
+        match path.and_then(|path| self.launcher.open(&path)) {
+          Ok(()) => self.running = false,
+          Err(e) => self.toast = Some(Toast::new(format!("Failed to open the config: {e}"), true)),
+        }
+      }
+      Action::Delete => self.pending_confirmation = Some(Confirmation::Delete(shortcut)),
+    }
+  }
+  /// Removes a shortcut of the config from the file and from the matches
+  fn delete(&mut self, shortcut: &SharedShortcut) {
+    match Config::remove_shortcut(shortcut) {
+      Ok(true) => {
+        for shortcuts in self.provided.iter_mut().flatten() {
+          shortcuts.retain(|s| !SharedShortcut::ptr_eq(s, shortcut));
+        }
+        self.found.retain(|s| !SharedShortcut::ptr_eq(s, shortcut));
+        if let Ok(config) = self.config.as_mut() {
+          config.shortcuts.retain(|s| s.seq != shortcut.seq || s.path != shortcut.path);
+        }
+        self.list_matches();
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        self.toast = Some(Toast::new(format!("Deleted {} from the config", shortcut.name()), false));
+      }
+      Ok(false) => self.toast = Some(Toast::new(format!("{} is not in the config file", shortcut.name()), true)),
+      Err(e) => self.toast = Some(Toast::new(format!("Failed to delete from the config: {e}"), true)),
+    }
+  }
+  /// Handles a key typed into the answer of `snippet_prompt`, copying the snippet after the last one
+  pub fn handle_prompt_key(&mut self, key_event: KeyEvent) {
+    let Some(prompt) = self.snippet_prompt.as_mut() else {
+      return;
+    };
+    match key_event.code {
+      KeyCode::Esc => self.snippet_prompt = None,
+      KeyCode::Backspace => {
+        prompt.input.pop();
+      }
+      KeyCode::Char(c) => prompt.input.push(c),
+      KeyCode::Enter => {
+        prompt.answers.push(std::mem::take(&mut prompt.input));
+        if prompt.answers.len() == prompt.names.len() {
+          let snippet = prompt.filled();
+          self.snippet_prompt = None;
+          let result = self.open_answered(&snippet);
+          self.toast_failure(result);
+        }
+      }
+      _ => {}
+    }
+  }
+  /// Closes bullet if the other way of opening worked, shows why it didn't otherwise
+  fn finish_failed_open(&mut self, result: std::io::Result<()>) {
+    match (result, self.failed_open.as_mut()) {
+      (Ok(()), _) => {
+        self.failed_open = None;
+        self.running = false;
+      }
+      (Err(e), Some(failed)) => {
+        error!(path = failed.shortcut.path, "failed to open another way: {e}");
+        failed.error = e.to_string();
+        failed.application = None;
+      }
+      (Err(_), None) => {}
+    }
+  }
+}
+
+/// Letters the labels of the rows are made of, the home row first
+const HINT_KEYS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// A row of the list of matches
+#[derive(Debug, Clone)]
+pub enum Entry {
+  /// Heading of a category, with how many of the matches are in it
+  Category { name: String, len: usize, collapsed: bool },
+  Shortcut(SharedShortcut),
+}
+
+impl Entry {
+  /// Heading of the last launched shortcuts
+  pub const RECENT: &str = "Recently launched";
+  /// Heading of the starred shortcuts
+  pub const FAVORITES: &str = "Favorites";
+  /// Heading of the shortcuts without a category, when others have one
+  pub const OTHER: &str = "Other";
+}
+
+/// What the confirmation popup waits for a yes to
+pub enum Confirmation {
+  /// Opening a shortcut with `confirm`
+  Open(Box<Shortcut>),
+  /// Deleting a shortcut from the config, picked in the action menu
+  Delete(SharedShortcut),
+}
+
+impl Confirmation {
+  pub fn shortcut(&self) -> &Shortcut {
+    match self {
+      Confirmation::Open(shortcut) => shortcut,
+      Confirmation::Delete(shortcut) => shortcut,
+    }
+  }
+}
+
+/// A shortcut the default application failed to open
+pub struct FailedOpen {
+  pub shortcut: Shortcut,
+  /// Why the last attempt failed
+  pub error: String,
+  /// What is typed as the application to open it with, `None` while picking an option
+  pub application: Option<String>,
+}
+
+/// What the action menu of a match can do with it. The config's `default_actions` picks the one
+/// Enter does for each kind out of `open`, `copy_path`, `reveal`, `terminal` and `editor`
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+  /// Opens the shortcut the way its kind is opened
+  Open,
+  /// Opens the path or URL with an application that is typed in
+  #[serde(skip_deserializing)]
+  OpenWith,
+  CopyPath,
+  /// Shows a File or Dir in the file manager
+  Reveal,
+  /// Opens a terminal in a Dir, or in the folder of a File
+  Terminal,
+  /// Opens a File or Dir in `$VISUAL` or `$EDITOR`
+  Editor,
+  /// Opens the config the shortcut is in
+  #[serde(skip_deserializing)]
+  Edit,
+  /// Stars or unstars the match
+  #[serde(skip_deserializing)]
+  Pin,
+  /// Removes the shortcut from the config
+  #[serde(skip_deserializing)]
+  Delete,
+}
+
+impl Action {
+  /// Every action, in the order of the menu
+  pub const ALL: [Action; 9] = [
+    Action::Open,
+    Action::OpenWith,
+    Action::CopyPath,
+    Action::Reveal,
+    Action::Terminal,
+    Action::Editor,
+    Action::Edit,
+    Action::Pin,
+    Action::Delete,
+  ];
+  /// Key that picks the action in the menu
+  pub fn key(self) -> char {
+    match self {
+      Action::Open => 'o',
+      Action::OpenWith => 'w',
+      Action::CopyPath => 'c',
+      Action::Reveal => 'r',
+      Action::Terminal => 't',
+      Action::Editor => 'e',
+      Action::Edit => 's',
+      Action::Pin => 'p',
+      Action::Delete => 'd',
+    }
+  }
+  pub fn label(self) -> &'static str {
+    match self {
+      Action::Open => "open",
+      Action::OpenWith => "open with…",
+      Action::CopyPath => "copy path",
+      Action::Reveal => "reveal in folder",
+      Action::Terminal => "open in terminal",
+      Action::Editor => "open in editor",
+      Action::Edit => "edit shortcut",
+      Action::Pin => "pin",
+      Action::Delete => "delete",
+    }
+  }
+  /// Whether the action makes sense for shortcuts of `kind`
+  pub fn applies_to(self, kind: ShortcutKind) -> bool {
+    match self {
+      Action::OpenWith => matches!(kind, ShortcutKind::Dir | ShortcutKind::File | ShortcutKind::Url),
+      Action::Reveal | Action::Terminal | Action::Editor => matches!(kind, ShortcutKind::Dir | ShortcutKind::File),
+      Action::Open | Action::CopyPath | Action::Edit | Action::Pin | Action::Delete => true,
+    }
+  }
+}
+
+/// Actions offered for the highlighted match
+pub struct ActionMenu {
+  /// The match the menu was opened on
+  pub shortcut: SharedShortcut,
+  pub actions: Vec<Action>,
+  /// Index of the highlighted action, picked with Enter
+  pub selected: usize,
+  /// What is typed as the application to open the match with, once open with… is picked
+  pub application: Option<String>,
+}
+
+/// The last launches, newest first, scrolled through with the arrows
+pub struct LaunchLog {
+  pub launches: Vec<Launch>,
+  /// Index of the launch listed first
+  pub offset: usize,
+}
+
+impl LaunchLog {
+  /// How many of the last launches are read
+  pub const LIMIT: usize = 200;
+}
+
+/// A snippet asking for the values of its `{?name}` placeholders, one after another
+pub struct SnippetPrompt {
+  pub shortcut: Shortcut,
+  /// Names of the placeholders, in the order they appear
+  pub names: Vec<String>,
+  /// Values of the names asked for so far
+  pub answers: Vec<String>,
+  /// What is typed as the value of the name being asked for
+  pub input: String,
+}
+
+impl SnippetPrompt {
+  /// Name being asked for
+  pub fn name(&self) -> &str {
+    &self.names[self.answers.len().min(self.names.len() - 1)]
+  }
+  /// The snippet with the answers in place of their placeholders
+  pub fn filled(&self) -> Shortcut {
+    let path = replace_placeholders(&self.shortcut.path, |placeholder| {
+      let name = placeholder.strip_prefix('?')?;
+      let index = self.names.iter().position(|n| n == name)?;
+      self.answers.get(index).cloned()
+    });
+    Shortcut { path, ..self.shortcut.clone() }
+  }
+}
+
+/// Note shown at the bottom of the results for a moment
+pub struct Toast {
+  pub text: String,
+  /// Whether it tells about a failure
+  pub failed: bool,
+  shown_at: std::time::Instant,
+}
+
+impl Toast {
+  const DURATION: Duration = Duration::from_secs(2);
+
+  fn new(text: String, failed: bool) -> Self {
+    Toast { text, failed, shown_at: std::time::Instant::now() }
+  }
+}
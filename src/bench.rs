@@ -0,0 +1,93 @@
+//! `bullet bench`: how long parsing the config, starting up and matching take with a synthetic
+//! config of many shortcuts, so regressions of the matcher can be measured
+
+use std::{ fmt::{ Display, Formatter }, sync::Arc, time::{ Duration, Instant } };
+use crate::{ app::App, config::Config, launcher::DryRunLauncher };
+
+/// Words the synthetic seqs, paths and descriptions are made of
+const WORDS: &[&str] = &[
+  "project", "notes", "docs", "github", "mail", "calendar", "music", "photos", "invoice", "backup",
+  "server", "deploy", "report", "budget", "recipes", "travel", "school", "design", "wiki", "games",
+];
+
+/// Queries typed one character at a time, the matching of every keystroke is timed
+const QUERIES: &[&str] = &["project42", "docs 7", "invoice", "zzz"];
+
+/// Times of one run of `run`
+pub struct BenchReport {
+  pub shortcuts: usize,
+  pub parse: Duration,
+  pub startup: Duration,
+  /// Time to match each keystroke of `QUERIES`
+  pub keystrokes: Vec<Duration>,
+}
+
+impl Display for BenchReport {
+  fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    let mut keystrokes = self.keystrokes.clone();
+    keystrokes.sort();
+    let total: Duration = keystrokes.iter().sum();
+    let mean = total / keystrokes.len().max(1) as u32;
+    let percentile = |p: usize| keystrokes.get(keystrokes.len().saturating_sub(1) * p / 100).copied().unwrap_or_default();
+    writeln!(f, "{} shortcuts", self.shortcuts)?;
+    writeln!(f, "  parse config  {:>10.2?}", self.parse)?;
+    writeln!(f, "  startup       {:>10.2?}", self.startup)?;
+    write!(
+      f,
+      "  matching      {:>10.2?} mean, {:.2?} p50, {:.2?} p95, {:.2?} max over {} keystrokes",
+      mean,
+      percentile(50),
+      percentile(95),
+      percentile(100),
+      keystrokes.len()
+    )
+  }
+}
+
+/// Config of `count` shortcuts of every kind with a path, spread over `WORDS`, and no providers
+pub fn synthetic_config(count: usize) -> String {
+  let shortcuts: Vec<serde_json::Value> = (0..count)
+    .map(|i| {
+      let word = WORDS[i % WORDS.len()];
+      let n = i / WORDS.len();
+      let (kind, path) = match i % 4 {
+        0 => ("url", format!("https://{word}.example/{n}")),
+        1 => ("file", format!("/bench/{word}/{n}.txt")),
+        2 => ("dir", format!("/bench/{word}/{n}")),
+        _ => ("app", format!("/bench/bin/{word}-{n}")),
+      };
+      serde_json::json!({
+        "path": path,
+        "seq": [format!("{word}{n}")],
+        "kind": kind,
+        "description": format!("{word} number {n}"),
+      })
+    })
+    .collect();
+  serde_json::json!({ "shortcuts": shortcuts, "providers": { "calculator": false } }).to_string()
+}
+
+/// Parses a synthetic config of `count` shortcuts, starts the app on it and types `QUERIES` into it,
+/// opening nothing
+pub fn run(count: usize) -> Result<BenchReport, serde_json::Error> {
+  let json = synthetic_config(count);
+  let started = Instant::now();
+  let config = serde_json::from_str::<Config>(&json)?;
+  let parse = started.elapsed();
+
+  let started = Instant::now();
+  let mut app = App::with_config(Ok(config), None).with_launcher(Arc::new(DryRunLauncher::default()));
+  app.wait_for_shortcuts();
+  let startup = started.elapsed();
+
+  let mut keystrokes = vec![];
+  for query in QUERIES {
+    app.update_matches("");
+    for end in query.char_indices().map(|(i, c)| i + c.len_utf8()) {
+      let started = Instant::now();
+      app.update_matches(&query[..end]);
+      keystrokes.push(started.elapsed());
+    }
+  }
+  Ok(BenchReport { shortcuts: count, parse, startup, keystrokes })
+}
@@ -0,0 +1,101 @@
+//! The system clipboard of X11, Wayland, Windows and macOS, which Snippets, Secrets, copied paths and
+//! calculator results go to
+
+use std::{ io::Write, process::{ Command, Stdio } };
+#[cfg(not(feature = "clipboard"))]
+use crate::missing_feature;
+
+/// Clipboard of the session, or an error saying why there is none, as in an SSH session
+#[cfg(feature = "clipboard")]
+fn open() -> std::io::Result<arboard::Clipboard> {
+  arboard::Clipboard::new().map_err(|e| {
+    std::io::Error::new(
+      std::io::ErrorKind::Unsupported,
+      format!("no clipboard is available, as over SSH without a display to forward to ({e})")
+    )
+  })
+}
+
+#[cfg(feature = "clipboard")]
+fn clipboard_error(e: arboard::Error) -> std::io::Error {
+  std::io::Error::other(format!("the clipboard failed: {e}"))
+}
+
+/// Puts text on the system clipboard. X11 and Wayland only hand out what a running process holds, so
+/// there a detached `bullet __hold-clipboard` holds `text` until something else is copied
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+  // opened here too, so a missing clipboard is reported rather than lost in the background
+  let mut clipboard = open()?;
+  if cfg!(all(unix, not(target_os = "macos"))) {
+    return spawn_detached("__hold-clipboard", &[], text);
+  }
+  clipboard.set_text(text).map_err(clipboard_error)
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> std::io::Result<()> {
+  Err(missing_feature("clipboard"))
+}
+
+/// Copies `text` and serves it to whoever pastes until something else is copied, for `bullet
+/// __hold-clipboard`
+#[cfg(all(feature = "clipboard", unix, not(target_os = "macos")))]
+pub fn hold_clipboard(text: &str) -> std::io::Result<()> {
+  use arboard::SetExtLinux;
+  open()?.set().wait().text(text).map_err(clipboard_error)
+}
+
+#[cfg(not(all(feature = "clipboard", unix, not(target_os = "macos"))))]
+pub fn hold_clipboard(text: &str) -> std::io::Result<()> {
+  copy_to_clipboard(text)
+}
+
+/// What the clipboard holds as text, empty when it holds nothing or no text
+#[cfg(feature = "clipboard")]
+pub fn read_clipboard() -> std::io::Result<String> {
+  match open()?.get_text() {
+    Ok(text) => Ok(text.trim_end_matches(['\r', '\n']).to_string()),
+    Err(arboard::Error::ContentNotAvailable) => Ok(String::new()),
+    Err(e) => Err(clipboard_error(e)),
+  }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn read_clipboard() -> std::io::Result<String> {
+  Err(missing_feature("clipboard"))
+}
+
+/// Empties the clipboard
+#[cfg(feature = "clipboard")]
+pub fn clear_clipboard() -> std::io::Result<()> {
+  open()?.clear().map_err(clipboard_error)
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn clear_clipboard() -> std::io::Result<()> {
+  Err(missing_feature("clipboard"))
+}
+
+/// Clears `text` from the clipboard after `seconds`, unless something else was copied meanwhile.
+/// A detached `bullet __clear-clipboard` waits for it, `text` is handed over on its stdin
+pub(crate) fn clear_clipboard_later(text: &str, seconds: u64) -> std::io::Result<()> {
+  spawn_detached("__clear-clipboard", &[&seconds.to_string()], text)
+}
+
+/// Runs a hidden subcommand of bullet in the background, outliving this process, with `text` on its
+/// stdin, where it stays out of the process list
+fn spawn_detached(subcommand: &str, args: &[&str], text: &str) -> std::io::Result<()> {
+  let mut command = Command::new(std::env::current_exe()?);
+  // its own process group is not hung up along with the terminal bullet ran in
+  #[cfg(unix)]
+  std::os::unix::process::CommandExt::process_group(&mut command, 0);
+  let mut child = command
+    .arg(subcommand)
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()?;
+  child.stdin.take().unwrap().write_all(text.as_bytes())
+}
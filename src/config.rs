@@ -0,0 +1,402 @@
+//! The config file, where it is looked for and the settings of the providers
+
+use core::fmt;
+use std::{
+  collections::BTreeMap,
+  fmt::{ Display, Formatter },
+  fs::{ self, File },
+  io::Read,
+  path::{ Path, PathBuf },
+};
+use directories::{ BaseDirs, UserDirs };
+use serde::Deserialize;
+use tracing::{ error, info };
+use crate::{ app::Action, launcher::TerminalEmulator, shortcut::{ Browser, Shortcut, ShortcutKind } };
+
+#[derive(Deserialize, Debug)]
+pub struct Config {
+  pub shortcuts: Vec<Shortcut>,
+  #[serde(default)]
+  pub(crate) providers: ProvidersConfig,
+  #[serde(default)]
+  pub daemon: DaemonConfig,
+  #[serde(default)]
+  pub columns: ColumnsConfig,
+  #[serde(default)]
+  pub sync: SyncConfig,
+  /// Browser the URL shortcuts without a `browser` of their own open in
+  #[serde(default)]
+  pub browser: Option<Browser>,
+  /// What Enter does with the shortcuts of a kind instead of opening them, like
+  /// `{ "dir": "terminal", "file": "editor" }`. The action menu offers the others
+  #[serde(default)]
+  pub default_actions: BTreeMap<ShortcutKind, Action>,
+  #[serde(default)]
+  pub terminal: TerminalConfig,
+  /// Web searches typed as `!<bang>` anywhere in the query, like `lofi beats !yt` with
+  /// `{ "yt": "https://www.youtube.com/results?search_query={query}" }`
+  #[serde(default)]
+  pub bangs: BTreeMap<String, String>,
+  /// How many of the last launched shortcuts are listed first while the query is empty, from the
+  /// launch history. None when 0
+  #[serde(default)]
+  pub recent: usize,
+  #[serde(default)]
+  pub order: Order,
+  #[serde(default)]
+  pub search: SearchConfig,
+}
+
+/// How the matches are ordered, favorites first either way
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Order {
+  /// By kind, then by how well they match the query
+  #[default]
+  Match,
+  /// The last opened first, from the launch history, whatever their kind and however they match.
+  /// The ones never opened come last, by how well they match
+  LastUsed,
+}
+
+impl Config {
+  /// Places the config is looked for in, in order: `documents/bullet` and the platform's
+  /// config dir, for systems without a documents dir
+  pub fn candidates() -> Vec<PathBuf> {
+    let documents = UserDirs::new()
+      .and_then(|dirs| dirs.document_dir().map(|dir| dir.join("bullet/config.json")));
+    let config = BaseDirs::new().map(|dirs| dirs.config_dir().join("bullet/config.json"));
+    documents.into_iter().chain(config).collect()
+  }
+  /// `BULLET_CONFIG` if it is set, the first candidate that exists otherwise
+  pub fn path() -> Result<PathBuf, LoadConfigError> {
+    if let Some(path) = std::env::var_os("BULLET_CONFIG") {
+      return Ok(PathBuf::from(path));
+    }
+    let candidates = Config::candidates();
+    match candidates.iter().find(|path| path.is_file()) {
+      Some(path) => Ok(path.clone()),
+      None if candidates.is_empty() => Err(LoadConfigError::NoConfig),
+      None => Err(LoadConfigError::NotFound(candidates)),
+    }
+  }
+  /// Directory the config is in, the one `path` names whether it exists or not
+  pub fn dir() -> Option<PathBuf> {
+    let path = std::path::absolute(Config::path().ok()?).ok()?;
+    path.parent().map(Path::to_path_buf)
+  }
+  pub fn load() -> Result<Config, LoadConfigError> {
+    let config_path = Config::path().inspect_err(|e| error!("no config to load: {e}"))?;
+    let config_path = config_path.display().to_string();
+    info!(path = config_path, "loading config");
+    let config_file = File::open(&config_path);
+    if config_file.is_err() {
+      let e = config_file.err().unwrap();
+      error!(path = config_path, "failed to open config: {e}");
+      if e.kind() == std::io::ErrorKind::NotFound {
+        return Err(LoadConfigError::NotFound(vec![PathBuf::from(config_path)]));
+      }
+      return Err(LoadConfigError::IoError(e));
+    }
+    let mut content = String::new();
+    if let Err(e) = config_file.and_then(|mut f| f.read_to_string(&mut content)) {
+      error!(path = config_path, "failed to read config: {e}");
+      return Err(LoadConfigError::IoError(e));
+    }
+    let config = serde_json::from_str::<Config>(&content);
+    match &config {
+      Ok(config) => info!(shortcuts = config.shortcuts.len(), "config loaded"),
+      Err(e) => error!(path = config_path, "failed to parse config: {e}"),
+    }
+    config.map_err(LoadConfigError::ParseError)
+  }
+  /// Removes the shortcuts with the seqs and path of `shortcut` from the config file, keeping the
+  /// order of the keys of the rest. Returns whether there were any
+  pub fn remove_shortcut(shortcut: &Shortcut) -> std::io::Result<bool> {
+    let path = Config::path().map_err(|e| std::io::Error::other(e.to_string()))?;
+    let mut config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    let Some(shortcuts) = config.get_mut("shortcuts").and_then(serde_json::Value::as_array_mut) else {
+      return Ok(false);
+    };
+    let len = shortcuts.len();
+    shortcuts.retain(|s| s["path"] != shortcut.path.as_str() || s["seq"] != serde_json::json!(shortcut.seq));
+    if shortcuts.len() == len {
+      return Ok(false);
+    }
+    info!(path = %path.display(), seq = shortcut.name(), "removing a shortcut from the config");
+    fs::write(&path, serde_json::to_string_pretty(&config)? + "\n")?;
+    Ok(true)
+  }
+}
+
+/// Which providers, besides the config's own shortcuts, are enabled
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct ProvidersConfig {
+  /// Start Menu shortcuts, Windows only
+  pub(crate) start_menu: bool,
+  /// Applications from XDG `.desktop` files, Linux and BSDs only
+  pub(crate) desktop_entries: bool,
+  /// `.app` bundles in `/Applications` and `~/Applications`, macOS only
+  pub(crate) applications: bool,
+  /// Executables found on `PATH`, searched behind `$`
+  pub(crate) path_executables: bool,
+  /// Chrome, Edge and Firefox bookmarks
+  pub(crate) bookmarks: bool,
+  /// Git repositories found under the given roots
+  pub(crate) git_repos: Option<GitReposConfig>,
+  /// Hosts of `~/.ssh/config` and `~/.ssh/known_hosts`
+  pub(crate) ssh_hosts: bool,
+  /// Running tmux sessions, and `tmux <name>` to create one
+  pub(crate) tmux: bool,
+  /// Open windows, searched behind `w:`
+  pub(crate) windows: bool,
+  /// Result of the query when it is arithmetic or a unit conversion, on by default
+  pub(crate) calculator: bool,
+  /// Exchange rates for converting currencies with the calculator
+  pub(crate) currency: Option<CurrencyConfig>,
+  /// Running processes, searched with `kill <name>` and terminated with Enter
+  pub(crate) processes: bool,
+  /// Files and folders under the given roots, searched behind `/`
+  pub(crate) file_index: Option<FileIndexConfig>,
+  /// Folders and workspaces recently opened in VS Code or VSCodium
+  pub(crate) vscode_recent: bool,
+  /// systemd user and system services, searched with `svc <name>`, Linux only
+  pub(crate) services: bool,
+  /// Docker containers and compose projects, searched with `docker <name>`.
+  /// Needs bullet built with the `docker` feature
+  pub(crate) docker: bool,
+  /// Password manager entries, searched with `pw <name>` for the password and `otp <name>` for the one-time code
+  pub(crate) passwords: Option<PasswordsConfig>,
+  /// Suggestions of the search engine while typing a search, fetched from the shortcut's `suggest` URL
+  pub(crate) web_suggestions: bool,
+  /// Commands answering queries with result rows, see `ExternalProvider`
+  pub(crate) external: Vec<ExternalProviderConfig>,
+  /// Lock, sleep, hibernate, restart, shut down and log out
+  pub(crate) system_actions: bool,
+  /// Seconds between collecting the shortcuts of every provider again while bullet is open
+  pub(crate) refresh_interval: u64,
+}
+
+impl Default for ProvidersConfig {
+  fn default() -> Self {
+    ProvidersConfig {
+      start_menu: false,
+      desktop_entries: false,
+      applications: false,
+      path_executables: false,
+      bookmarks: false,
+      git_repos: None,
+      ssh_hosts: false,
+      tmux: false,
+      windows: false,
+      calculator: true,
+      currency: None,
+      processes: false,
+      file_index: None,
+      vscode_recent: false,
+      services: false,
+      docker: false,
+      passwords: None,
+      web_suggestions: false,
+      external: vec![],
+      system_actions: false,
+      refresh_interval: 300,
+    }
+  }
+}
+
+/// Columns of the results table that are only shown when turned on
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ColumnsConfig {
+  /// How long ago each shortcut was last opened, from the launch history
+  pub last_used: bool,
+  /// How many times the shortcuts opened often were opened, like a dim `×41` after their aliases
+  pub launch_count: bool,
+}
+
+/// Looks of the search box
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SearchConfig {
+  /// Dim text shown in the search box while nothing is typed, like `Search shortcuts`
+  pub placeholder: Option<String>,
+  /// Symbol before what is typed, like `❯`
+  pub prompt: Option<String>,
+  /// Title on the border of the search box, like the name of the config in use when several are
+  /// switched between with `BULLET_CONFIG`
+  pub title: Option<String>,
+}
+
+/// Settings of `bullet sync`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SyncConfig {
+  /// Git remote the config is pushed to and pulled from, like `git@github.com:me/bullet-config.git`.
+  /// Changes are only committed locally without one
+  pub remote: Option<String>,
+  /// Branch of the remote the config is kept on
+  pub branch: String,
+}
+
+impl Default for SyncConfig {
+  fn default() -> Self {
+    SyncConfig { remote: None, branch: "main".to_string() }
+  }
+}
+
+/// Terminal emulator the ssh, terminal and editor shortcuts open in. `program` and `args` as in
+/// `TerminalEmulator`, overridden on one platform by `windows`, `macos` or `linux`. A common
+/// emulator is looked for when none is set
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TerminalConfig {
+  pub program: Option<String>,
+  pub args: Vec<String>,
+  pub windows: Option<TerminalEmulator>,
+  pub macos: Option<TerminalEmulator>,
+  pub linux: Option<TerminalEmulator>,
+}
+
+impl TerminalConfig {
+  /// The emulator of this platform, the configured one or the one detected
+  pub fn emulator(&self) -> Option<TerminalEmulator> {
+    let platform = if cfg!(windows) {
+      &self.windows
+    } else if cfg!(target_os = "macos") {
+      &self.macos
+    } else {
+      &self.linux
+    };
+    let configured = self.program
+      .as_ref()
+      .map(|program| TerminalEmulator { program: program.clone(), args: self.args.clone() });
+    platform.clone().or(configured).or_else(TerminalEmulator::detect)
+  }
+}
+
+/// Settings of `bullet daemon`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DaemonConfig {
+  /// Global hotkey that opens the picker, modifiers and a key joined with `+` like `alt+space`
+  pub hotkey: String,
+}
+
+impl Default for DaemonConfig {
+  fn default() -> Self {
+    DaemonConfig { hotkey: "alt+space".to_string() }
+  }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct GitReposConfig {
+  pub(crate) roots: Vec<String>,
+  /// How many directories deep repositories are looked for under each root
+  #[serde(default = "GitReposConfig::default_depth")]
+  pub(crate) depth: usize,
+  /// Editor command that also gets an `edit <repo>` shortcut per repository
+  pub(crate) editor: Option<String>,
+}
+
+impl GitReposConfig {
+  fn default_depth() -> usize {
+    3
+  }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct FileIndexConfig {
+  pub(crate) roots: Vec<String>,
+  /// Names left out of the index, `*` and `?` match any characters.
+  /// Patterns of `.gitignore` and `.ignore` files are respected too
+  #[serde(default)]
+  pub(crate) ignore: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct ExternalProviderConfig {
+  pub(crate) command: String,
+  #[serde(default)]
+  pub(crate) args: Vec<String>,
+  /// Only queries starting with it are sent, without it
+  pub(crate) prefix: Option<String>,
+  /// Milliseconds the command gets to answer before it is killed
+  #[serde(default = "ExternalProviderConfig::default_timeout")]
+  pub(crate) timeout: u64,
+}
+
+impl ExternalProviderConfig {
+  fn default_timeout() -> u64 {
+    2000
+  }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PasswordManager {
+  /// The standard unix password manager, with the pass-otp extension for one-time codes
+  #[serde(rename = "pass")]
+  Pass,
+  /// The `bw` CLI, which needs an unlocked session in `BW_SESSION`
+  #[serde(rename = "bitwarden")]
+  Bitwarden,
+  /// The `op` CLI, signed in
+  #[serde(rename = "1password")]
+  OnePassword,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct PasswordsConfig {
+  pub(crate) manager: PasswordManager,
+  /// Seconds after which a copied password is cleared from the clipboard
+  #[serde(default = "PasswordsConfig::default_clear_after")]
+  pub(crate) clear_after: u64,
+}
+
+impl PasswordsConfig {
+  fn default_clear_after() -> u64 {
+    45
+  }
+}
+
+/// Where exchange rates come from. Without it currencies are not converted
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct CurrencyConfig {
+  /// Url answering with `{"base": "USD", "rates": {"EUR": 0.92, ...}}`, fetched at most daily
+  pub(crate) rates_url: Option<String>,
+  /// File with rates in the same format, used instead of the url
+  pub(crate) rates_file: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum LoadConfigError {
+  IoError(std::io::Error),
+  ParseError(serde_json::Error),
+  /// None of the places the config is looked for in exist on this system
+  NoConfig,
+  /// The config is in none of the places it was looked for in
+  NotFound(Vec<PathBuf>),
+}
+
+impl Display for LoadConfigError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      LoadConfigError::IoError(e) => write!(f, "{e}"),
+      LoadConfigError::ParseError(e) => write!(f, "{e}"),
+      LoadConfigError::NoConfig =>
+        write!(
+          f,
+          "There is no documents or config directory to look for the config in, set BULLET_CONFIG to its path"
+        ),
+      LoadConfigError::NotFound(paths) => {
+        let paths: Vec<String> = paths.iter().map(|p| format!("\"{}\"", p.display())).collect();
+        write!(f, "Config does not exist in {}, `bullet init` writes one", paths.join(" or "))
+      }
+    }
+  }
+}
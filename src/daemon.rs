@@ -0,0 +1,152 @@
+//! `bullet daemon`: stays running with the providers warm, opens the picker on a global hotkey
+//! and the shortcuts of `bullet://` links
+
+use std::{ process::Command, str::FromStr, time::Duration };
+use global_hotkey::{ hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState };
+use serde::{ Deserialize, Serialize };
+use tracing::{ error, info };
+use crate::{
+  app::{ App, BulletError },
+  config::{ Config, TerminalConfig },
+  instance::{ Instance, DAEMON },
+  launcher::{ spawn_detached, terminal_command },
+};
+
+/// What other bullets ask of the daemon
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum DaemonRequest {
+  /// Open the shortcut of a seq, which a `bullet://` link named
+  Open(String),
+  /// Type a Snippet with `auto_type` into the focused window, then move the cursor `cursor_back`
+  /// characters left to where its `{cursor}` was
+  Type { text: String, cursor_back: usize },
+}
+
+/// Registers the hotkey, `hotkey` or the config's, and opens the picker in a new terminal window
+/// whenever it is pressed. The app stays loaded meanwhile, its workers keep the provider caches
+/// fresh so the picker starts on them. Runs until bullet is killed
+pub fn run(hotkey: Option<String>) -> Result<(), BulletError> {
+  let instance = Instance::<DaemonRequest>
+    ::claim(DAEMON)
+    .map_err(|e| BulletError::Daemon(e.to_string()))?
+    .ok_or(BulletError::Daemon("another daemon is running".to_string()))?;
+  let config = Config::load().map_err(BulletError::Config)?;
+  let hotkey = hotkey.unwrap_or(config.daemon.hotkey.clone());
+  let hotkey = HotKey
+    ::from_str(&hotkey)
+    .map_err(|e| BulletError::Daemon(format!("invalid hotkey \"{hotkey}\": {e}")))?;
+  // dropping the manager unregisters the hotkey
+  let manager = GlobalHotKeyManager::new().map_err(|e| BulletError::Daemon(e.to_string()))?;
+  manager
+    .register(hotkey)
+    .map_err(|e| BulletError::Daemon(format!("failed to register {}: {e}", hotkey.into_string())))?;
+  info!(hotkey = hotkey.into_string(), "daemon listening");
+  let terminal = config.terminal.clone();
+  let mut app = App::with_config(Ok(config), None);
+  app.wait_for_shortcuts();
+  loop {
+    pump_events();
+    if let Ok(event) = GlobalHotKeyEvent::receiver().recv_timeout(Duration::from_millis(50)) {
+      if event.id == hotkey.id() && event.state == HotKeyState::Pressed {
+        summon(&terminal);
+      }
+    }
+    for request in instance.requests() {
+      match request {
+        DaemonRequest::Open(seq) => {
+          info!(seq, "opening from a link");
+          if let Err(e) = app.open_linked(&seq) {
+            error!("failed to open from a link: {e}");
+          }
+        }
+        DaemonRequest::Type { text, cursor_back } => type_text(text, cursor_back),
+      }
+    }
+    // the refreshed shortcuts are only collected for their caches, but would pile up unread
+    app.receive();
+  }
+}
+
+/// Opens the picker in a new terminal window
+fn summon(terminal: &TerminalConfig) {
+  let Ok(exe) = std::env::current_exe() else {
+    error!("failed to find the bullet executable");
+    return;
+  };
+  info!("opening the picker");
+  let emulator = terminal.emulator();
+  let command = if cfg!(windows) && emulator.is_none() {
+    // `start` gives bullet its own console, which closes with it
+    let mut command = Command::new("cmd");
+    command.args(["/c", "start", ""]).arg(exe);
+    command
+  } else {
+    terminal_command(emulator.as_ref(), exe.as_os_str(), &[], None)
+  };
+  if let Err(e) = spawn_detached(command) {
+    error!("failed to open the picker: {e}");
+  }
+}
+
+/// Types a snippet on a thread of its own, after giving the picker a moment to close so the keys
+/// reach the window it was opened over
+#[cfg(feature = "autotype")]
+fn type_text(text: String, cursor_back: usize) {
+  use enigo::{ Direction, Enigo, Key, Keyboard, Settings };
+  std::thread::spawn(move || {
+    std::thread::sleep(Duration::from_millis(300));
+    info!(chars = text.chars().count(), "typing a snippet");
+    let typed = Enigo::new(&Settings::default()).map_err(|e| e.to_string()).and_then(|mut enigo| {
+      enigo.text(&text).map_err(|e| e.to_string())?;
+      for _ in 0..cursor_back {
+        enigo.key(Key::LeftArrow, Direction::Click).map_err(|e| e.to_string())?;
+      }
+      Ok(())
+    });
+    if let Err(e) = typed {
+      error!("failed to type a snippet: {e}");
+    }
+  });
+}
+
+#[cfg(not(feature = "autotype"))]
+fn type_text(_text: String, _cursor_back: usize) {
+  error!("not typing a snippet, bullet was built without the autotype feature");
+}
+
+/// Hands the hotkey messages of the hidden window the hotkey is registered with to it,
+/// Windows only delivers them to the thread that registered it
+#[cfg(windows)]
+fn pump_events() {
+  use windows_sys::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW,
+    PeekMessageW,
+    TranslateMessage,
+    MSG,
+    PM_REMOVE,
+  };
+  unsafe {
+    let mut message: MSG = std::mem::zeroed();
+    while PeekMessageW(&mut message, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+      TranslateMessage(&message);
+      DispatchMessageW(&message);
+    }
+  }
+}
+
+/// Runs the main run loop once, the hotkey events of macOS are dispatched from it
+#[cfg(target_os = "macos")]
+fn pump_events() {
+  #[link(name = "CoreFoundation", kind = "framework")]
+  extern "C" {
+    static kCFRunLoopDefaultMode: *const std::ffi::c_void;
+    fn CFRunLoopRunInMode(mode: *const std::ffi::c_void, seconds: f64, return_after_source: u8) -> i32;
+  }
+  unsafe {
+    CFRunLoopRunInMode(kCFRunLoopDefaultMode, 0.0, 1);
+  }
+}
+
+/// X11 hotkeys are read on a thread of their own
+#[cfg(not(any(windows, target_os = "macos")))]
+fn pump_events() {}
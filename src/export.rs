@@ -0,0 +1,104 @@
+//! `bullet export`: the URL shortcuts as a Netscape bookmarks file, which every browser imports, or
+//! every shortcut as a shell alias
+
+use std::collections::BTreeMap;
+use clap::ValueEnum;
+use crate::shortcut::{ replace_placeholders, Shortcut, ShortcutKind };
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum Shell {
+  Bash,
+  Zsh,
+  Fish,
+  Powershell,
+}
+
+/// Bookmarks and the folders below them, by name
+#[derive(Default)]
+struct Folder<'a> {
+  folders: BTreeMap<String, Folder<'a>>,
+  bookmarks: Vec<&'a Shortcut>,
+}
+
+impl<'a> Folder<'a> {
+  fn write(&self, html: &mut String, depth: usize) {
+    let indent = "    ".repeat(depth);
+    for (name, folder) in &self.folders {
+      html.push_str(&format!("{indent}<DT><H3>{}</H3>\n{indent}<DL><p>\n", escape(name)));
+      folder.write(html, depth + 1);
+      html.push_str(&format!("{indent}</DL><p>\n"));
+    }
+    for shortcut in &self.bookmarks {
+      // search URLs and templates become keyword bookmarks, `<seq> <query>` typed in the address bar
+      let url = replace_placeholders(&shortcut.get_prefixed_path(), |placeholder| {
+        matches!(placeholder, "query" | "query:raw" | "args" | "args:raw").then(|| "%s".to_string())
+      });
+      let keyword = match shortcut.seq.first() {
+        Some(seq) if url.contains("%s") => format!(" SHORTCUTURL=\"{}\"", escape(seq)),
+        _ => String::new(),
+      };
+      let title = shortcut.description.clone().unwrap_or(shortcut.name().to_string());
+      html.push_str(&format!("{indent}<DT><A HREF=\"{}\"{keyword}>{}</A>\n", escape(&url), escape(&title)));
+    }
+  }
+}
+
+fn escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Bookmarks file of the URL shortcuts, in the folder of their category, or else nested in the
+/// folders of their tags, which bookmarks collected from a browser have
+pub fn bookmarks_html(shortcuts: &[Shortcut]) -> String {
+  let mut root = Folder::default();
+  for shortcut in shortcuts.iter().filter(|s| s.kind == ShortcutKind::Url && !s.seq.is_empty()) {
+    let path = match &shortcut.category {
+      Some(category) => std::slice::from_ref(category),
+      None => shortcut.tags.as_slice(),
+    };
+    let folder = path.iter().fold(&mut root, |folder, name| folder.folders.entry(name.clone()).or_default());
+    folder.bookmarks.push(shortcut);
+  }
+  let mut html = String::from(
+    "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+     <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+     <TITLE>Bookmarks</TITLE>\n\
+     <H1>Bookmarks</H1>\n\
+     <DL><p>\n"
+  );
+  root.write(&mut html, 1);
+  html.push_str("</DL><p>\n");
+  html
+}
+
+/// Whether a seq can name an alias in every shell without quoting
+fn alias_name(seq: &str) -> bool {
+  !seq.starts_with('-') && !seq.is_empty() && seq.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Aliases running `bullet run <seq> --` for every seq that is a valid name, so arguments typed after
+/// them reach apps and terminal commands. Dirs `cd` into their path instead, a child process couldn't
+pub fn shell_aliases(shortcuts: &[Shortcut], shell: Shell) -> String {
+  let mut script = String::new();
+  for shortcut in shortcuts {
+    let path = shortcut.get_prefixed_path();
+    for seq in shortcut.seq.iter().filter(|seq| alias_name(seq)) {
+      let line = match (shell, shortcut.kind == ShortcutKind::Dir) {
+        (Shell::Bash | Shell::Zsh, true) => format!("alias {seq}={}", sh_quote(&format!("cd {}", sh_quote(&path)))),
+        (Shell::Bash | Shell::Zsh, false) => format!("alias {seq}='bullet run {seq} --'"),
+        (Shell::Fish, true) => format!("function {seq}; cd {}; end", sh_quote(&path)),
+        (Shell::Fish, false) => format!("function {seq}; bullet run {seq} -- $argv; end"),
+        (Shell::Powershell, true) => format!("function {seq} {{ Set-Location -LiteralPath '{}' }}", path.replace('\'', "''")),
+        (Shell::Powershell, false) => format!("function {seq} {{ bullet run {seq} -- @args }}"),
+      };
+      script.push_str(&line);
+      script.push('\n');
+    }
+  }
+  script
+}
+
+/// `text` in single quotes, which POSIX shells and fish take literally but for the quote itself
+fn sh_quote(text: &str) -> String {
+  format!("'{}'", text.replace('\'', "'\\''"))
+}
@@ -0,0 +1,62 @@
+//! Starred shortcuts, kept in the data dir rather than written into the user's config
+
+use std::{ collections::BTreeSet, fs, path::PathBuf };
+use tracing::error;
+use crate::{ data_dir, shortcut::Shortcut };
+
+/// The seqs of the starred shortcuts, saved as a JSON list
+#[derive(Default)]
+pub struct Favorites {
+  /// File the favorites are saved in, they are only kept in memory without one
+  path: Option<PathBuf>,
+  seqs: BTreeSet<String>,
+}
+
+impl Favorites {
+  /// The favorites saved in the data dir
+  pub fn load() -> Favorites {
+    match data_dir() {
+      Some(dir) => Favorites::at(dir.join("favorites.json")),
+      None => Favorites::default(),
+    }
+  }
+  /// The favorites saved in `path`, none while it does not exist
+  pub fn at(path: PathBuf) -> Favorites {
+    let seqs = match fs::read_to_string(&path) {
+      Ok(content) =>
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+          error!(path = %path.display(), "invalid favorites: {e}");
+          BTreeSet::new()
+        }),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+      Err(e) => {
+        error!(path = %path.display(), "failed to read the favorites: {e}");
+        BTreeSet::new()
+      }
+    };
+    Favorites { path: Some(path), seqs }
+  }
+  pub fn contains(&self, shortcut: &Shortcut) -> bool {
+    shortcut.seq.first().is_some_and(|seq| self.seqs.contains(seq))
+  }
+  /// Stars the shortcut, or unstars it when it is starred, and saves. Returns whether it is starred.
+  /// Favorites are kept by seq, a shortcut without one can't be starred
+  pub fn toggle(&mut self, shortcut: &Shortcut) -> std::io::Result<bool> {
+    let Some(seq) = shortcut.seq.first() else {
+      return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "a shortcut without a seq can't be starred"));
+    };
+    let mut seqs = self.seqs.clone();
+    let starred = !seqs.remove(seq);
+    if starred {
+      seqs.insert(seq.clone());
+    }
+    if let Some(path) = &self.path {
+      if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+      }
+      fs::write(path, serde_json::to_string_pretty(&seqs)?)?;
+    }
+    self.seqs = seqs;
+    Ok(starred)
+  }
+}
@@ -0,0 +1,305 @@
+//! `bullet import`: the shortcuts of other launchers, converted to the ones of the config
+
+use std::{ collections::HashMap, path::Path };
+use clap::ValueEnum;
+use serde::{ Deserialize, Serialize };
+use crate::{ providers::seq_candidates, shortcut::ShortcutKind };
+
+/// Launcher an export is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+  /// Flow Launcher's `Settings.json` custom shortcuts, or the settings of its Web Searches plugin
+  Flow,
+  /// Wox's `Settings.json` custom shortcuts, or the settings of its WebSearch plugin
+  Wox,
+  /// Alfred's web searches, the `prefs.plist` of `preferences/features/websearch` as XML
+  Alfred,
+  /// Raycast's quicklinks export
+  Raycast,
+}
+
+/// A shortcut as it is written in the config, with the fields an import fills in
+#[derive(Serialize, Debug, Clone)]
+pub struct ImportedShortcut {
+  pub path: String,
+  pub seq: Vec<String>,
+  pub kind: ShortcutKind,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+}
+
+impl ImportedShortcut {
+  /// A shortcut opening `target`: a URL, or a folder or file depending on what is there
+  pub(crate) fn to(target: &str, seq: Vec<String>, description: Option<String>) -> Self {
+    let kind = if target.contains("://") {
+      ShortcutKind::Url
+    } else if Path::new(target).is_dir() {
+      ShortcutKind::Dir
+    } else {
+      ShortcutKind::File
+    };
+    ImportedShortcut { path: target.to_string(), seq, kind, description }
+  }
+}
+
+/// Settings of Flow Launcher and Wox, and of their web search plugins
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FlowSettings {
+  #[serde(default)]
+  custom_shortcuts: Vec<FlowShortcut>,
+  /// Flow Launcher's Web Searches plugin
+  #[serde(default)]
+  search_sources: Vec<FlowSearch>,
+  /// Wox's WebSearch plugin
+  #[serde(default)]
+  web_searches: Vec<FlowSearch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FlowShortcut {
+  key: String,
+  value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FlowSearch {
+  title: String,
+  action_keyword: String,
+  url: String,
+  #[serde(default = "enabled")]
+  enabled: bool,
+}
+
+fn enabled() -> bool {
+  true
+}
+
+/// A Raycast quicklink, `{Query}` and `{argument ...}` in its link are filled with what is typed
+#[derive(Deserialize)]
+struct RaycastQuicklink {
+  name: String,
+  link: String,
+}
+
+/// Reads the shortcuts out of an export of `format`
+pub fn import(format: ImportFormat, content: &str) -> Result<Vec<ImportedShortcut>, String> {
+  match format {
+    ImportFormat::Flow | ImportFormat::Wox => {
+      let settings = serde_json::from_str::<FlowSettings>(content).map_err(|e| e.to_string())?;
+      // custom shortcuts expand into a query, only the ones expanding into a URL or path open something
+      let shortcuts = settings.custom_shortcuts
+        .into_iter()
+        .filter(|s| s.value.contains("://") || Path::new(&s.value).is_absolute())
+        .map(|s| ImportedShortcut::to(&s.value, vec![s.key], None));
+      let searches = settings.search_sources
+        .into_iter()
+        .chain(settings.web_searches)
+        .filter(|search| search.enabled)
+        .map(|search| ImportedShortcut {
+          path: search.url.replace("{q}", "{query}"),
+          seq: vec![search.action_keyword],
+          kind: ShortcutKind::Url,
+          description: Some(search.title),
+        });
+      Ok(shortcuts.chain(searches).collect())
+    }
+    ImportFormat::Alfred => {
+      if content.starts_with("bplist") {
+        return Err("binary plist, convert it with `plutil -convert xml1` first".to_string());
+      }
+      Ok(
+        plist_dicts(content)
+          .into_iter()
+          .filter(|site| site.get("enabled").is_none_or(|enabled| enabled == "true"))
+          .filter_map(|site| {
+            // the text is what Alfred shows, like `Search GitHub for '{query}'`
+            let text = site.get("text").map(|text| text.replace("'{query}'", "").replace("{query}", ""));
+            Some(ImportedShortcut {
+              path: site.get("url")?.clone(),
+              seq: vec![site.get("keyword")?.clone()],
+              kind: ShortcutKind::Url,
+              description: text.map(|text| text.trim().to_string()),
+            })
+          })
+          .collect()
+      )
+    }
+    ImportFormat::Raycast => {
+      let quicklinks = serde_json::from_str::<Vec<RaycastQuicklink>>(content).map_err(|e| e.to_string())?;
+      Ok(
+        quicklinks
+          .into_iter()
+          .map(|quicklink| {
+            let link = fill_raycast_placeholders(&quicklink.link);
+            ImportedShortcut::to(&link, seq_candidates(&quicklink.name), Some(quicklink.name))
+          })
+          .collect()
+      )
+    }
+  }
+}
+
+/// Turns Raycast's `{Query}` placeholders into `{query}` and its `{argument name="..."}` ones,
+/// which are put in the link as they are typed, into `{args}`
+fn fill_raycast_placeholders(link: &str) -> String {
+  let mut filled = String::new();
+  let mut rest = link;
+  while let Some(start) = rest.find('{') {
+    let Some(end) = rest[start..].find('}') else {
+      break;
+    };
+    let placeholder = &rest[start + 1..start + end];
+    filled.push_str(&rest[..start]);
+    if placeholder.eq_ignore_ascii_case("query") {
+      filled.push_str("{query}");
+    } else if placeholder.starts_with("argument") {
+      filled.push_str("{args}");
+    } else {
+      filled.push_str(&rest[start..=start + end]);
+    }
+    rest = &rest[start + end + 1..];
+  }
+  filled.push_str(rest);
+  filled
+}
+
+/// The keys of each dict of an XML plist that holds no other dict, with their string and boolean
+/// values. Enough for Alfred's web searches, which are such dicts under `customSites`
+fn plist_dicts(xml: &str) -> Vec<HashMap<String, String>> {
+  let mut dicts = vec![];
+  let mut dict = HashMap::new();
+  let mut key: Option<String> = None;
+  let mut rest = xml;
+  while let Some(start) = rest.find('<') {
+    let Some(end) = rest[start..].find('>') else {
+      break;
+    };
+    let tag = &rest[start + 1..start + end];
+    let after = &rest[start + end + 1..];
+    let text = || after.split('<').next().map(unescape_xml).unwrap_or_default();
+    match tag {
+      "dict" => dict.clear(),
+      "/dict" if !dict.is_empty() => dicts.push(std::mem::take(&mut dict)),
+      "key" => key = Some(text()),
+      "string" => {
+        if let Some(key) = key.take() {
+          dict.insert(key, text());
+        }
+      }
+      "true/" | "false/" => {
+        if let Some(key) = key.take() {
+          dict.insert(key, tag.trim_end_matches('/').to_string());
+        }
+      }
+      _ => {}
+    }
+    rest = after;
+  }
+  dicts
+}
+
+fn unescape_xml(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Seq, path, kind and description of each imported shortcut
+  fn imported(format: ImportFormat, content: &str) -> Vec<(String, String, ShortcutKind, Option<String>)> {
+    import(format, content)
+      .unwrap()
+      .into_iter()
+      .map(|s| (s.seq.join(","), s.path, s.kind, s.description))
+      .collect()
+  }
+
+  #[test]
+  fn imports_flow_shortcuts_and_web_searches() {
+    let settings = r#"{
+      "CustomShortcuts": [
+        { "Key": "gh", "Value": "https://github.com" },
+        { "Key": "w", "Value": "weather" }
+      ],
+      "SearchSources": [
+        { "Title": "YouTube", "ActionKeyword": "yt", "Url": "https://youtube.com/results?q={q}" },
+        { "Title": "Off", "ActionKeyword": "off", "Url": "https://off.example/{q}", "Enabled": false }
+      ]
+    }"#;
+    assert_eq!(
+      imported(ImportFormat::Flow, settings),
+      [
+        ("gh".to_string(), "https://github.com".to_string(), ShortcutKind::Url, None),
+        (
+          "yt".to_string(),
+          "https://youtube.com/results?q={query}".to_string(),
+          ShortcutKind::Url,
+          Some("YouTube".to_string())
+        ),
+      ]
+    );
+    assert!(import(ImportFormat::Wox, "not json").is_err());
+  }
+
+  #[test]
+  fn imports_alfred_web_searches() {
+    let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+      <plist version="1.0"><dict><key>customSites</key><dict>
+        <key>A1</key><dict>
+          <key>enabled</key><true/>
+          <key>keyword</key><string>mdn</string>
+          <key>text</key><string>Search MDN for '{query}'</string>
+          <key>url</key><string>https://developer.mozilla.org/search?q={query}&amp;a=1</string>
+        </dict>
+        <key>B2</key><dict>
+          <key>enabled</key><false/>
+          <key>keyword</key><string>off</string>
+          <key>url</key><string>https://off.example</string>
+        </dict>
+      </dict></dict></plist>"#;
+    assert_eq!(
+      imported(ImportFormat::Alfred, plist),
+      [(
+        "mdn".to_string(),
+        "https://developer.mozilla.org/search?q={query}&a=1".to_string(),
+        ShortcutKind::Url,
+        Some("Search MDN for".to_string())
+      )]
+    );
+    assert!(import(ImportFormat::Alfred, "bplist00").is_err());
+  }
+
+  #[test]
+  fn imports_raycast_quicklinks() {
+    let quicklinks = r#"[
+      { "name": "Google Maps", "link": "https://maps.google.com/?q={Query}" },
+      { "name": "Issue", "link": "https://tracker.example/{argument name=\"id\"}/{other}" }
+    ]"#;
+    assert_eq!(
+      imported(ImportFormat::Raycast, quicklinks),
+      [
+        (
+          "google maps,gm".to_string(),
+          "https://maps.google.com/?q={query}".to_string(),
+          ShortcutKind::Url,
+          Some("Google Maps".to_string())
+        ),
+        (
+          "issue".to_string(),
+          "https://tracker.example/{args}/{other}".to_string(),
+          ShortcutKind::Url,
+          Some("Issue".to_string())
+        ),
+      ]
+    );
+  }
+}
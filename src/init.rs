@@ -0,0 +1,201 @@
+//! `bullet init`: the first config, written after asking where it goes and for a few shortcuts, with
+//! the browser, terminal and editor found on this system
+
+use std::{ fs, io::{ BufRead, Write }, path::{ Path, PathBuf }, process::Command };
+use clap::ValueEnum;
+use serde_json::{ json, Value };
+use crate::{
+  config::Config,
+  import::ImportedShortcut,
+  launcher::{ find_in_path, TerminalEmulator },
+  shortcut::{ Browser, ShortcutKind },
+};
+
+/// Editors looked for on PATH when neither `$VISUAL` nor `$EDITOR` is set, in order, with whether
+/// they run inside a terminal
+const EDITORS: &[(&str, bool)] = &[
+  ("nvim", true),
+  ("vim", true),
+  ("hx", true),
+  ("micro", true),
+  ("nano", true),
+  ("code", false),
+  ("zed", false),
+];
+
+/// Browser, terminal emulator and editor found on this system
+#[derive(Debug, Clone, Default)]
+pub struct Detected {
+  pub browser: Option<Browser>,
+  pub terminal: Option<TerminalEmulator>,
+  /// Program of the editor, with whether it runs inside a terminal
+  pub editor: Option<(String, bool)>,
+}
+
+impl Detected {
+  pub fn detect() -> Self {
+    Detected { browser: default_browser(), terminal: TerminalEmulator::detect(), editor: editor() }
+  }
+}
+
+/// `$BROWSER`, or the default browser of the desktop on Linux when it is on PATH. Elsewhere the
+/// system opens URLs in the default browser without one
+fn default_browser() -> Option<Browser> {
+  if let Some(browser) = std::env::var("BROWSER").ok().filter(|b| !b.is_empty()) {
+    return Some(Browser { command: browser, args: vec![] });
+  }
+  if !cfg!(all(unix, not(target_os = "macos"))) {
+    return None;
+  }
+  let output = Command::new("xdg-settings").args(["get", "default-web-browser"]).output().ok()?;
+  let desktop_file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  let command = desktop_file.strip_suffix(".desktop")?.to_string();
+  find_in_path(&command).map(|_| Browser { command, args: vec![] })
+}
+
+/// `$VISUAL` or `$EDITOR`, taken to run inside a terminal, or the first of `EDITORS` on PATH
+fn editor() -> Option<(String, bool)> {
+  let configured = ["VISUAL", "EDITOR"]
+    .iter()
+    .find_map(|name| std::env::var(name).ok().filter(|editor| !editor.is_empty()));
+  if let Some(editor) = configured {
+    let terminal = EDITORS.iter().find(|(program, _)| editor.ends_with(program)).is_none_or(|(_, terminal)| *terminal);
+    return Some((editor, terminal));
+  }
+  EDITORS
+    .iter()
+    .find(|(program, _)| find_in_path(program).is_some())
+    .map(|(program, terminal)| (program.to_string(), *terminal))
+}
+
+/// Seq suggested for a target: the name of a URL's site, or the name of a file, folder or app
+fn suggested_seq(target: &str) -> String {
+  let name = match target.split_once("://") {
+    Some((_, rest)) => {
+      let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+      let host = host.trim_start_matches("www.");
+      host.split('.').next().unwrap_or(host).to_string()
+    }
+    None => Path::new(target).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+  };
+  name.to_lowercase().replace(' ', "-")
+}
+
+/// A shortcut opening `target`, an app when it names a program on PATH
+fn shortcut(target: &str, seq: String, description: Option<String>) -> ImportedShortcut {
+  let mut shortcut = ImportedShortcut::to(target, vec![seq], description);
+  if shortcut.kind == ShortcutKind::File && !Path::new(target).exists() && find_in_path(target).is_some() {
+    shortcut.kind = ShortcutKind::App;
+  }
+  shortcut
+}
+
+/// The config with `shortcuts` and what was `detected`. JSON has no comments, the `//` keys explain
+/// the settings instead and are ignored when it is loaded
+pub fn config_text(shortcuts: &[ImportedShortcut], detected: &Detected, config_path: &Path) -> String {
+  let mut shortcuts: Vec<Value> = shortcuts.iter().map(|s| serde_json::to_value(s).unwrap()).collect();
+  if let Some((editor, terminal)) = &detected.editor {
+    shortcuts.push(json!({
+      "//": "your editor, `bullet run edit -- notes.txt` opens notes.txt in it",
+      "path": editor,
+      "seq": ["edit"],
+      "kind": "app",
+      "terminal": terminal,
+    }));
+  }
+  shortcuts.push(json!({
+    "//": "this file",
+    "path": config_path.display().to_string(),
+    "seq": ["config"],
+    "kind": "file",
+    "description": "The config of bullet",
+  }));
+  let mut config = serde_json::Map::new();
+  config.insert(
+    "//".to_string(),
+    json!("Config of bullet. Each shortcut opens its `path` when one of its `seq` is typed, its `kind` tells what it is, like app, dir, file, url, terminal, snippet or ssh. `bullet doctor` checks it")
+  );
+  config.insert("shortcuts".to_string(), Value::Array(shortcuts));
+  if let Some(browser) = &detected.browser {
+    config.insert(
+      "browser".to_string(),
+      json!({ "//": "the URL shortcuts open in it, remove it for the default browser of the system", "command": browser.command, "args": browser.args })
+    );
+  }
+  if let Some(terminal) = &detected.terminal {
+    config.insert(
+      "terminal".to_string(),
+      json!({ "//": "the terminal and ssh shortcuts open in it", "program": terminal.program, "args": terminal.args })
+    );
+  }
+  config.insert(
+    "providers".to_string(),
+    json!({ "//": "shortcuts found on the system besides these, like installed apps and browser bookmarks, each of them can be turned off with false" })
+  );
+  serde_json::to_string_pretty(&Value::Object(config)).unwrap() + "\n"
+}
+
+/// Prints `question` and reads the answer, trimmed, or `default` when it is empty
+fn ask(input: &mut impl BufRead, output: &mut impl Write, question: &str, default: &str) -> std::io::Result<String> {
+  if default.is_empty() {
+    write!(output, "{question}: ")?;
+  } else {
+    write!(output, "{question} [{default}]: ")?;
+  }
+  output.flush()?;
+  let mut answer = String::new();
+  if input.read_line(&mut answer)? == 0 {
+    return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "the answers ended early"));
+  }
+  let answer = answer.trim();
+  Ok(if answer.is_empty() { default.to_string() } else { answer.to_string() })
+}
+
+/// Asks where the config goes and for its shortcuts, then writes it with what `detected` found.
+/// Returns where it was written
+pub fn run(input: &mut impl BufRead, output: &mut impl Write, detected: &Detected) -> std::io::Result<PathBuf> {
+  let candidates = match std::env::var_os("BULLET_CONFIG") {
+    Some(path) => vec![PathBuf::from(path)],
+    None => Config::candidates(),
+  };
+  writeln!(output, "Where should the config go? bullet looks for it in:")?;
+  for (i, candidate) in candidates.iter().enumerate() {
+    writeln!(output, "  {}) {}", i + 1, candidate.display())?;
+  }
+  let location = ask(input, output, "Number or another path", if candidates.is_empty() { "" } else { "1" })?;
+  let path = match location.parse::<usize>() {
+    Ok(n) if (1..=candidates.len()).contains(&n) => candidates[n - 1].clone(),
+    _ => PathBuf::from(location),
+  };
+  if path.exists() && ask(input, output, &format!("{} exists, replace it? y/n", path.display()), "n")? != "y" {
+    return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, format!("{} is kept", path.display())));
+  }
+
+  writeln!(output, "\nAdd a few shortcuts: a URL, a folder, a file or an app. Leave it empty to finish")?;
+  let mut shortcuts = vec![];
+  loop {
+    let target = ask(input, output, "Target", "")?;
+    if target.is_empty() {
+      break;
+    }
+    let seq = ask(input, output, "Typed as", &suggested_seq(&target))?;
+    let description = ask(input, output, "Description", "")?;
+    let shortcut = shortcut(&target, seq, Some(description).filter(|d| !d.is_empty()));
+    let kind = shortcut.kind.to_possible_value().unwrap();
+    writeln!(output, "  added {} -> {} ({})", shortcut.seq[0], shortcut.path, kind.get_name())?;
+    shortcuts.push(shortcut);
+  }
+
+  writeln!(output, "\nFound on this system:")?;
+  let found = |what: Option<String>| what.unwrap_or_else(|| "none".to_string());
+  writeln!(output, "  browser   {}", found(detected.browser.as_ref().map(|b| b.command.clone())))?;
+  writeln!(output, "  terminal  {}", found(detected.terminal.as_ref().map(|t| t.program.clone())))?;
+  writeln!(output, "  editor    {}", found(detected.editor.as_ref().map(|(editor, _)| editor.clone())))?;
+
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  fs::write(&path, config_text(&shortcuts, detected, &path))?;
+  writeln!(output, "\nWrote {}", path.display())?;
+  Ok(path)
+}
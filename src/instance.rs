@@ -0,0 +1,127 @@
+//! Keeps a single picker and daemon running: a second `bullet` hands its request over to the running
+//! one instead. The running one holds a lock file and listens on a local socket, a named pipe on Windows.
+//! Both are the user's own: the socket is in the data dir only the user can get into, and a named
+//! pipe only lets its owner write to it by default
+
+use std::{
+  fs::{ self, File, TryLockError },
+  io::{ BufRead, BufReader, Write },
+  path::PathBuf,
+  sync::mpsc::{ channel, Receiver, Sender },
+};
+use interprocess::local_socket::{
+  prelude::*,
+  GenericFilePath,
+  GenericNamespaced,
+  ListenerOptions,
+  Name,
+  Stream,
+};
+use serde::{ de::DeserializeOwned, Deserialize, Serialize };
+use tracing::{ error, info };
+use crate::data_dir;
+
+/// Name of the open picker
+pub const PICKER: &str = "bullet";
+/// Name of `bullet daemon`
+pub const DAEMON: &str = "bullet-daemon";
+
+/// What a second bullet asks of the open picker
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Summon {
+  /// Typed into the search, replacing what is there
+  pub query: Option<String>,
+}
+
+/// The running picker or daemon, holding the lock until it is dropped. Requests are sent to it
+/// as lines of JSON
+pub struct Instance<T> {
+  _lock: File,
+  requests: Receiver<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Send + 'static> Instance<T> {
+  /// Takes the place of the running `name`, or returns `None` if one is running already
+  pub fn claim(name: &str) -> std::io::Result<Option<Instance<T>>> {
+    let dir = private_data_dir()?;
+    let lock = File::options()
+      .create(true)
+      .truncate(false)
+      .write(true)
+      .open(dir.join(format!("{name}.lock")))?;
+    match lock.try_lock() {
+      Ok(()) => {}
+      Err(TryLockError::WouldBlock) => {
+        return Ok(None);
+      }
+      Err(TryLockError::Error(e)) => {
+        return Err(e);
+      }
+    }
+    // with the lock held a socket file left behind by a crashed bullet is safe to replace
+    let listener = ListenerOptions::new()
+      .name(Instance::<T>::socket_name(name)?)
+      .try_overwrite(true)
+      .create_sync()?;
+    let (sender, requests) = channel();
+    std::thread::spawn(move || {
+      for stream in listener.incoming() {
+        let mut line = String::new();
+        let read = stream.and_then(|stream| BufReader::new(stream).read_line(&mut line));
+        match read.map(|_| serde_json::from_str::<T>(&line)) {
+          Ok(Ok(request)) => {
+            if sender.send(request).is_err() {
+              return;
+            }
+          }
+          Ok(Err(e)) => error!("invalid request from another bullet: {e}"),
+          Err(e) => error!("failed to read from another bullet: {e}"),
+        }
+      }
+    });
+    Ok(Some(Instance { _lock: lock, requests }))
+  }
+  /// Hands `request` over to the running `name`, failing when none is running
+  pub fn send(name: &str, request: &T) -> std::io::Result<()> {
+    info!(name, "handing over to the running bullet");
+    let mut stream = Stream::connect(Instance::<T>::socket_name(name)?)?;
+    writeln!(stream, "{}", serde_json::to_string(request)?)
+  }
+  /// Name of the socket, a file in the data dir, or a named pipe of the user on Windows. Never in
+  /// the abstract namespace, which any user could connect to
+  fn socket_name(name: &str) -> std::io::Result<Name<'static>> {
+    if cfg!(windows) {
+      let user = std::env::var("USERNAME").unwrap_or_default();
+      return format!("{name}-{user}.sock").to_ns_name::<GenericNamespaced>();
+    }
+    private_data_dir()?.join(format!("{name}.sock")).to_fs_name::<GenericFilePath>()
+  }
+  /// The requests that came in since the last call
+  pub fn requests(&self) -> Vec<T> {
+    self.requests.try_iter().collect()
+  }
+  /// Sends the requests on to `sender` as they come in, wrapped by `wrap`, rather than keeping them
+  /// for `requests`, so a loop blocks on one channel for them and its other events
+  pub fn forward<U: Send + 'static>(&mut self, sender: Sender<U>, wrap: fn(T) -> U) {
+    let requests = std::mem::replace(&mut self.requests, channel().1);
+    std::thread::spawn(move || {
+      for request in requests {
+        if sender.send(wrap(request)).is_err() {
+          return;
+        }
+      }
+    });
+  }
+}
+
+/// The data dir, created if need be, which only the user can get into on Unix
+fn private_data_dir() -> std::io::Result<PathBuf> {
+  let dir = data_dir().ok_or(std::io::Error::other("there is no data directory for the lock and socket"))?;
+  fs::create_dir_all(&dir)?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+  }
+  Ok(dir)
+}
@@ -0,0 +1,702 @@
+//! Opening shortcuts, the clipboard, scripts and the launch history
+
+use std::{
+  collections::HashMap,
+  fs,
+  io::Write,
+  path::{ Path, PathBuf },
+  process::{ Command, Stdio },
+  sync::Mutex,
+};
+use clap::ValueEnum;
+use serde::Deserialize;
+use tracing::error;
+use crate::{
+  clipboard::{ clear_clipboard_later, copy_to_clipboard, read_clipboard },
+  config::TerminalConfig,
+  data_dir,
+  shortcut::{ fill_url, replace_placeholders, Shortcut, ShortcutKind },
+};
+#[cfg(not(all(feature = "autotype", feature = "scripting")))]
+use crate::missing_feature;
+
+/// Brings the terminal window bullet runs in to the front, as far as the platform tells which one it is
+pub(crate) fn raise_terminal_window() -> std::io::Result<()> {
+  if cfg!(windows) {
+    focus_window(&std::process::id().to_string())
+  } else if cfg!(target_os = "macos") {
+    let terminal = match std::env::var("TERM_PROGRAM").as_deref() {
+      Ok("Apple_Terminal") | Err(_) => "Terminal".to_string(),
+      Ok("iTerm.app") => "iTerm".to_string(),
+      Ok(other) => other.to_string(),
+    };
+    focus_window(&terminal)
+  } else {
+    // set by xterm, kitty, alacritty and most other X11 terminals
+    let id = std::env::var("WINDOWID").map_err(|_| std::io::Error::other("WINDOWID is not set"))?;
+    focus_window(&id)
+  }
+}
+
+/// Opens the folder holding `path` with it selected: Explorer's `/select`, Finder's `open -R` and
+/// the FileManager1 D-Bus interface most Linux file managers implement. Where that is missing
+/// the folder is just opened
+fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+  if cfg!(windows) {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    // explorer exits with 1 even when it worked, so only starting it is checked
+    return Command::new("explorer").arg(select).spawn().map(|_| ());
+  }
+  if cfg!(target_os = "macos") {
+    let status = Command::new("open").arg("-R").arg(path).status()?;
+    return if status.success() {
+      Ok(())
+    } else {
+      Err(std::io::Error::other(format!("revealing failed with {status}")))
+    };
+  }
+  let uri: String = std::iter
+    ::once("file://".to_string())
+    .chain(
+      path
+        .to_string_lossy()
+        .bytes()
+        .map(|b| {
+          if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            (b as char).to_string()
+          } else {
+            format!("%{b:02X}")
+          }
+        })
+    )
+    .collect();
+  let shown = Command::new("dbus-send")
+    .args([
+      "--session",
+      "--dest=org.freedesktop.FileManager1",
+      "--type=method_call",
+      "/org/freedesktop/FileManager1",
+      "org.freedesktop.FileManager1.ShowItems",
+      &format!("array:string:{uri}"),
+      "string:",
+    ])
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .is_ok_and(|status| status.success());
+  if shown {
+    return Ok(());
+  }
+  open::that_detached(path.parent().unwrap_or(path))
+}
+
+/// Brings the window of a Window shortcut to the front
+fn focus_window(id: &str) -> std::io::Result<()> {
+  let mut command = if cfg!(windows) {
+    let mut command = Command::new("powershell");
+    command.args([
+      "-NoProfile",
+      "-Command",
+      &format!("(New-Object -ComObject WScript.Shell).AppActivate({id})"),
+    ]);
+    command
+  } else if cfg!(target_os = "macos") {
+    let mut command = Command::new("osascript");
+    command.args(["-e", &format!("tell application \"{}\" to activate", id.replace("\"", "\\\""))]);
+    command
+  } else {
+    let mut command = Command::new("wmctrl");
+    command.args(["-i", "-a", id]);
+    command
+  };
+  let status = command.status()?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(std::io::Error::other(format!("focusing the window failed with {status}")))
+  }
+}
+
+/// Terminates the process of a Process shortcut
+fn kill_process(pid: &str) -> std::io::Result<()> {
+  let status = if cfg!(windows) {
+    Command::new("taskkill").args(["/PID", pid, "/F"]).stdout(Stdio::null()).status()?
+  } else {
+    Command::new("kill").args(["-TERM", pid]).status()?
+  };
+  if status.success() {
+    Ok(())
+  } else {
+    Err(std::io::Error::other(format!("killing {pid} failed with {status}")))
+  }
+}
+
+/// Quotes an argument for a posix shell
+fn shell_quote(arg: &str) -> String {
+  format!("'{}'", arg.replace("'", "'\\''"))
+}
+
+/// Finds an executable by name in the directories of `PATH`
+pub fn find_in_path(program: &str) -> Option<PathBuf> {
+  let extensions: &[&str] = if cfg!(windows) { &["", ".exe", ".cmd", ".bat"] } else { &[""] };
+  std::env
+    ::var_os("PATH")
+    .iter()
+    .flat_map(|paths| std::env::split_paths(paths).collect::<Vec<_>>())
+    .flat_map(|dir| extensions.iter().map(move |ext| dir.join(format!("{program}{ext}"))))
+    .find(|candidate| candidate.is_file())
+}
+
+/// A terminal emulator and how it is told to run a command in a directory, like
+/// `{ "program": "wezterm", "args": ["start", "--cwd", "{cwd}", "--", "{cmd}"] }`
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TerminalEmulator {
+  /// Program of the emulator, a path or a name on PATH
+  pub program: String,
+  /// Its arguments. `{cwd}` is replaced with the directory it starts in, and an argument that is
+  /// only `{cmd}` with the command and its arguments, which go last without one
+  #[serde(default)]
+  pub args: Vec<String>,
+}
+
+impl TerminalEmulator {
+  /// Emulators looked for on PATH when none is configured, in order, with the platforms they are
+  /// looked for on
+  const KNOWN: &[(&str, &[&str], bool)] = &[
+    ("wt", &["-d", "{cwd}", "{cmd}"], true),
+    ("wezterm", &["start", "--cwd", "{cwd}", "--", "{cmd}"], true),
+    ("kitty", &["--directory", "{cwd}", "{cmd}"], false),
+    ("alacritty", &["--working-directory", "{cwd}", "-e", "{cmd}"], true),
+    ("gnome-terminal", &["--working-directory={cwd}", "--", "{cmd}"], false),
+  ];
+  /// `$TERMINAL` outside of Windows, or the first of the common emulators on PATH
+  pub fn detect() -> Option<TerminalEmulator> {
+    if !cfg!(windows) {
+      if let Some(terminal) = std::env::var("TERMINAL").ok().filter(|t| !t.is_empty()) {
+        return Some(TerminalEmulator { program: terminal, args: vec!["-e".to_string(), "{cmd}".to_string()] });
+      }
+    }
+    TerminalEmulator::KNOWN
+      .iter()
+      .filter(|(_, _, on_windows)| *on_windows || !cfg!(windows))
+      .find(|(program, ..)| find_in_path(program).is_some())
+      .map(|(program, args, _)| TerminalEmulator {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+      })
+  }
+  /// Command opening the emulator running `program` with `args` in `dir`, bullet's current
+  /// directory when `None`
+  fn command(&self, program: &std::ffi::OsStr, args: &[String], dir: Option<&Path>) -> Command {
+    let dir = dir.map(Path::to_path_buf).or_else(|| std::env::current_dir().ok()).unwrap_or_default();
+    let cwd = dir.to_string_lossy();
+    let mut command = Command::new(&self.program);
+    for arg in &self.args {
+      if arg == "{cmd}" {
+        command.arg(program).args(args);
+      } else {
+        command.arg(arg.replace("{cwd}", &cwd));
+      }
+    }
+    if !self.args.iter().any(|arg| arg == "{cmd}") {
+      command.arg(program).args(args);
+    }
+    command.current_dir(dir);
+    command
+  }
+}
+
+/// Command running `program` with `args` in a new window of `emulator`, or of the platform's
+/// terminal without one
+pub(crate) fn terminal_command(
+  emulator: Option<&TerminalEmulator>,
+  program: &std::ffi::OsStr,
+  args: &[String],
+  dir: Option<&Path>
+) -> Command {
+  if let Some(emulator) = emulator {
+    return emulator.command(program, args, dir);
+  }
+  let mut command = if cfg!(windows) {
+    let mut command = Command::new("cmd");
+    command.args(["/c", "start", "", "cmd", "/k"]).arg(program).args(args);
+    command
+  } else if cfg!(target_os = "macos") {
+    let program = program.to_string_lossy();
+    let line: Vec<String> = std::iter
+      ::once(program.as_ref())
+      .chain(args.iter().map(|a| a.as_str()))
+      .map(shell_quote)
+      .collect();
+    let script = line.join(" ").replace("\\", "\\\\").replace("\"", "\\\"");
+    let mut command = Command::new("osascript");
+    command.args(["-e", &format!("tell application \"Terminal\" to do script \"{script}\"")]);
+    command
+  } else {
+    let mut command = Command::new("x-terminal-emulator");
+    command.arg("-e").arg(program).args(args);
+    command
+  };
+  if let Some(dir) = dir {
+    command.current_dir(dir);
+  }
+  command
+}
+
+/// Fills in the placeholders of a Snippet: `{date}` with today, or formatted like `{date:%d.%m.%Y}`,
+/// `{clipboard}` with what is copied and `{uuid}` with a random UUID. `{cursor}` is kept, it marks
+/// where the cursor goes when the snippet is typed
+pub(crate) fn expand_snippet(text: &str) -> std::io::Result<String> {
+  let mut error = None;
+  let expanded = replace_placeholders(text, |placeholder| {
+    match placeholder.split_once(':').unwrap_or((placeholder, "%Y-%m-%d")) {
+      ("date", format) => Some(chrono::Local::now().format(format).to_string()),
+      ("clipboard", _) => read_clipboard().map_err(|e| error = Some(e)).ok(),
+      ("uuid", _) => Some(uuid::Uuid::new_v4().to_string()),
+      _ => None,
+    }
+  });
+  match error {
+    Some(e) => Err(e),
+    None => Ok(expanded),
+  }
+}
+
+/// Appends a line with the time and `text` to the file of a Note, as a list item to markdown files
+fn append_note(path: &Path, text: &str) -> std::io::Result<()> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  let bullet = if path.extension().is_some_and(|e| e == "md") { "- " } else { "" };
+  let time = chrono::Local::now().format("%Y-%m-%d %H:%M");
+  let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+  writeln!(file, "{bullet}{time} {text}")
+}
+
+/// Hands a Snippet to `bullet daemon` to type once the picker is gone and the window it was opened
+/// over has the focus again
+#[cfg(feature = "autotype")]
+fn type_in_daemon(text: &str) -> std::io::Result<()> {
+  use crate::{ daemon::DaemonRequest, instance::{ Instance, DAEMON } };
+  let (before, after) = text.split_once("{cursor}").unwrap_or((text, ""));
+  let after = after.replace("{cursor}", "");
+  let request = DaemonRequest::Type { text: format!("{before}{after}"), cursor_back: after.chars().count() };
+  Instance::send(DAEMON, &request).map_err(|e| {
+    std::io::Error::new(e.kind(), format!("snippets with auto_type are typed by `bullet daemon`: {e}"))
+  })
+}
+
+#[cfg(not(feature = "autotype"))]
+fn type_in_daemon(_text: &str) -> std::io::Result<()> {
+  Err(missing_feature("autotype"))
+}
+
+/// Runs the command of a Secret shortcut and copies the first line it prints
+fn copy_secret(shortcut: &Shortcut) -> std::io::Result<()> {
+  let output = Command::new(&shortcut.path).args(&shortcut.args).stderr(Stdio::null()).output()?;
+  if !output.status.success() {
+    // the output is not part of the error, it may hold the secret
+    return Err(std::io::Error::other(format!("{} failed with {}", shortcut.path, output.status)));
+  }
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let secret = stdout.lines().next().unwrap_or_default();
+  copy_to_clipboard(secret)?;
+  match shortcut.clear_after {
+    Some(seconds) => clear_clipboard_later(secret, seconds),
+    None => Ok(()),
+  }
+}
+
+/// Shows a desktop notification
+#[cfg(feature = "scripting")]
+fn notify(title: &str, body: &str) -> std::io::Result<()> {
+  let command = if cfg!(windows) {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut command = Command::new("powershell");
+    command.args([
+      "-NoProfile",
+      "-Command",
+      &format!(
+        "Add-Type -AssemblyName System.Windows.Forms; $n = New-Object System.Windows.Forms.NotifyIcon; $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; $n.ShowBalloonTip(5000, {}, {}, 'Info'); Start-Sleep 5; $n.Dispose()",
+        quote(title),
+        quote(body)
+      ),
+    ]);
+    command
+  } else if cfg!(target_os = "macos") {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut command = Command::new("osascript");
+    command.args(["-e", &format!("display notification {} with title {}", quote(body), quote(title))]);
+    command
+  } else {
+    let mut command = Command::new("notify-send");
+    command.args([title, body]);
+    command
+  };
+  spawn_detached(command)
+}
+
+/// Rhai engine with the helpers scripts get:
+/// `open(target)`, `copy(text)`, `notify(title, body)`, `env(name)` and `date(format)` with strftime formats
+#[cfg(feature = "scripting")]
+pub fn script_engine() -> rhai::Engine {
+  let mut engine = rhai::Engine::new();
+  let script_error = |e: std::io::Error| -> Box<rhai::EvalAltResult> { e.to_string().into() };
+  engine.register_fn("open", move |target: &str| open::that_detached(target).map_err(script_error));
+  engine.register_fn("copy", move |text: &str| copy_to_clipboard(text).map_err(script_error));
+  engine.register_fn("notify", move |title: &str, body: &str| notify(title, body).map_err(script_error));
+  engine.register_fn("env", |name: &str| std::env::var(name).unwrap_or_default());
+  engine.register_fn("date", |format: &str| chrono::Local::now().format(format).to_string());
+  engine
+}
+
+/// Source of a Script shortcut, read from the file when `path` names a `.rhai` file
+pub fn script_source(shortcut: &Shortcut) -> std::io::Result<String> {
+  let path = shortcut.get_prefixed_path();
+  if path.ends_with(".rhai") {
+    fs::read_to_string(path)
+  } else {
+    Ok(path)
+  }
+}
+
+#[cfg(feature = "scripting")]
+fn run_script(shortcut: &Shortcut) -> std::io::Result<()> {
+  let source = script_source(shortcut)?;
+  script_engine().run(&source).map_err(|e| std::io::Error::other(e.to_string()))
+}
+
+#[cfg(not(feature = "scripting"))]
+fn run_script(_shortcut: &Shortcut) -> std::io::Result<()> {
+  Err(missing_feature("scripting"))
+}
+
+/// Checks that a script parses, without running it
+#[cfg(feature = "scripting")]
+pub fn compile_script(source: &str) -> Result<(), String> {
+  script_engine().compile(source).map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "scripting"))]
+pub fn compile_script(_source: &str) -> Result<(), String> {
+  Err(missing_feature("scripting").to_string())
+}
+
+/// Starts a process without tying its stdio to the terminal bullet runs in
+pub(crate) fn spawn_detached(mut command: Command) -> std::io::Result<()> {
+  command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn().map(|_| ())
+}
+
+/// Opens a shortcut. Apps with args or a terminal are started directly,
+/// everything else is handed to the system's default handler
+fn launch(shortcut: &Shortcut, terminal: Option<&TerminalEmulator>) -> std::io::Result<()> {
+  if let Some(prefix) = shortcut.path_prefix.filter(|prefix| prefix.dir().is_none()) {
+    return Err(
+      std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("there is no {} directory on this system", prefix.name())
+      )
+    );
+  }
+  let path = shortcut.get_prefixed_path();
+  if shortcut.kind == ShortcutKind::Process {
+    return kill_process(&shortcut.path);
+  }
+  if shortcut.kind == ShortcutKind::Snippet {
+    let text = expand_snippet(&shortcut.path)?;
+    if shortcut.auto_type {
+      return type_in_daemon(&text);
+    }
+    let text = text.replace("{cursor}", "");
+    copy_to_clipboard(&text)?;
+    return match shortcut.clear_after {
+      Some(seconds) => clear_clipboard_later(&text, seconds),
+      None => Ok(()),
+    };
+  }
+  if shortcut.kind == ShortcutKind::Secret {
+    return copy_secret(shortcut);
+  }
+  if shortcut.kind == ShortcutKind::Script {
+    return run_script(shortcut);
+  }
+  if let (ShortcutKind::Note, Some(text)) = (shortcut.kind, shortcut.args.first()) {
+    return append_note(&shortcut.prefixed_path(), text);
+  }
+  if shortcut.kind == ShortcutKind::Window {
+    return focus_window(&shortcut.path);
+  }
+  if shortcut.kind == ShortcutKind::Ssh {
+    return spawn_detached(terminal_command(terminal, "ssh".as_ref(), &[path], None));
+  }
+  if shortcut.terminal || shortcut.kind == ShortcutKind::Terminal {
+    return spawn_detached(terminal_command(terminal, shortcut.prefixed_path().as_os_str(), &shortcut.args, None));
+  }
+  if shortcut.kind == ShortcutKind::App && !shortcut.args.is_empty() {
+    let mut command = Command::new(shortcut.prefixed_path());
+    command.args(&shortcut.args);
+    return spawn_detached(command);
+  }
+  if shortcut.kind == ShortcutKind::Url {
+    // a search URL or template opened without a query
+    let url = fill_url(&fill_url(&path, "query", ""), "args", "");
+    if let Some(browser) = &shortcut.browser {
+      let mut command = Command::new(&browser.command);
+      command.args(&browser.args);
+      if shortcut.incognito {
+        let flag = browser.private_flag().ok_or(
+          std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("no private window flag is known for {}", browser.command)
+          )
+        )?;
+        command.arg(flag);
+      }
+      command.arg(url);
+      return spawn_detached(command);
+    }
+    if shortcut.incognito {
+      return Err(
+        std::io::Error::new(
+          std::io::ErrorKind::Unsupported,
+          "a private window needs a browser, set `browser` on the shortcut or in the config"
+        )
+      );
+    }
+    return open::that_detached(url);
+  }
+  open::that_detached(shortcut.prefixed_path())
+}
+
+/// Everything the app opens goes through, so matching and launching can run without side effects
+pub trait Launcher: Send + Sync {
+  /// Opens a shortcut the way its kind is opened
+  fn launch(&self, shortcut: &Shortcut) -> std::io::Result<()>;
+  /// Opens a path with its default application
+  fn open(&self, path: &Path) -> std::io::Result<()>;
+  /// Opens a path with the given application
+  fn open_with(&self, path: &Path, application: &str) -> std::io::Result<()>;
+  /// Opens the folder holding a path in the file manager, with the path selected
+  fn reveal(&self, path: &Path) -> std::io::Result<()>;
+  fn copy(&self, text: &str) -> std::io::Result<()>;
+  /// Opens a new terminal window in a directory
+  fn open_terminal(&self, dir: &Path) -> std::io::Result<()>;
+  /// Opens a path in the user's editor
+  fn edit(&self, path: &Path) -> std::io::Result<()>;
+  /// Whether the launches are recorded in the history
+  fn records_history(&self) -> bool {
+    true
+  }
+}
+
+/// Opens shortcuts for real, the terminal ones in the emulator of the config's `terminal`
+pub struct SystemLauncher {
+  terminal: TerminalConfig,
+}
+
+impl SystemLauncher {
+  pub fn new(terminal: TerminalConfig) -> Self {
+    SystemLauncher { terminal }
+  }
+}
+
+impl Launcher for SystemLauncher {
+  fn launch(&self, shortcut: &Shortcut) -> std::io::Result<()> {
+    let emulator = self.terminal.emulator();
+    for copy in 0..shortcut.copies() {
+      if copy > 0 {
+        std::thread::sleep(std::time::Duration::from_millis(shortcut.count_delay.unwrap_or(0)));
+      }
+      launch(shortcut, emulator.as_ref())?;
+    }
+    Ok(())
+  }
+  fn open(&self, path: &Path) -> std::io::Result<()> {
+    open::that_detached(path)
+  }
+  fn open_with(&self, path: &Path, application: &str) -> std::io::Result<()> {
+    open::with_detached(path, application)
+  }
+  fn reveal(&self, path: &Path) -> std::io::Result<()> {
+    reveal_in_file_manager(path)
+  }
+  fn copy(&self, text: &str) -> std::io::Result<()> {
+    copy_to_clipboard(text)
+  }
+  fn open_terminal(&self, dir: &Path) -> std::io::Result<()> {
+    let emulator = self.terminal.emulator();
+    if cfg!(target_os = "macos") && emulator.is_none() {
+      // Terminal starts in the folder it is handed, a script it runs starts in the home dir
+      let mut command = Command::new("open");
+      command.args(["-a", "Terminal"]).arg(dir);
+      return spawn_detached(command);
+    }
+    let shell = if cfg!(windows) {
+      "cmd".to_string()
+    } else {
+      std::env::var("SHELL").ok().filter(|shell| !shell.is_empty()).unwrap_or("sh".to_string())
+    };
+    spawn_detached(terminal_command(emulator.as_ref(), shell.as_ref(), &[], Some(dir)))
+  }
+  fn edit(&self, path: &Path) -> std::io::Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+      .into_iter()
+      .find_map(|name| std::env::var(name).ok().filter(|editor| !editor.is_empty()));
+    match editor {
+      Some(editor) => {
+        let args = [path.to_string_lossy().to_string()];
+        spawn_detached(terminal_command(self.terminal.emulator().as_ref(), editor.as_ref(), &args, None))
+      }
+      // without an editor set the default application of the file is the next best thing
+      None => open::that_detached(path),
+    }
+  }
+}
+
+/// Opens nothing, only notes what would have been opened, for `--dry-run`
+#[derive(Default)]
+pub struct DryRunLauncher {
+  opened: Mutex<Vec<String>>,
+}
+
+impl DryRunLauncher {
+  fn note(&self, what: String) -> std::io::Result<()> {
+    self.opened.lock().unwrap().push(what);
+    Ok(())
+  }
+  /// What would have been opened so far, in order
+  pub fn opened(&self) -> Vec<String> {
+    self.opened.lock().unwrap().clone()
+  }
+}
+
+impl Launcher for DryRunLauncher {
+  fn launch(&self, shortcut: &Shortcut) -> std::io::Result<()> {
+    if let (ShortcutKind::Note, Some(text)) = (shortcut.kind, shortcut.args.first()) {
+      return self.note(format!("append \"{text}\" to {}", shortcut.get_prefixed_path()));
+    }
+    let kind = shortcut.kind.to_possible_value().unwrap();
+    let browser = shortcut.browser.as_ref().map(|browser| format!(" in {}", browser.command)).unwrap_or_default();
+    let args: String = shortcut.args.iter().map(|arg| format!(" {}", shell_quote(arg))).collect();
+    let copies = match shortcut.copies() {
+      1 => String::new(),
+      copies => format!(" {copies} times"),
+    };
+    self.note(format!("open {} {}{args}{browser}{copies}", kind.get_name(), shortcut.get_prefixed_path()))
+  }
+  fn open(&self, path: &Path) -> std::io::Result<()> {
+    self.note(format!("open {}", path.display()))
+  }
+  fn open_with(&self, path: &Path, application: &str) -> std::io::Result<()> {
+    self.note(format!("open {} with {application}", path.display()))
+  }
+  fn reveal(&self, path: &Path) -> std::io::Result<()> {
+    self.note(format!("reveal {}", path.display()))
+  }
+  fn copy(&self, text: &str) -> std::io::Result<()> {
+    self.note(format!("copy {text}"))
+  }
+  fn open_terminal(&self, dir: &Path) -> std::io::Result<()> {
+    self.note(format!("open a terminal in {}", dir.display()))
+  }
+  fn edit(&self, path: &Path) -> std::io::Result<()> {
+    self.note(format!("edit {}", path.display()))
+  }
+  fn records_history(&self) -> bool {
+    false
+  }
+}
+
+/// A launch recorded in the history
+pub struct Launch {
+  pub seq: String,
+  pub path: String,
+  pub kind: ShortcutKind,
+  /// What was typed to find the shortcut
+  pub query: String,
+  /// Unix timestamp in seconds
+  pub launched_at: i64,
+  /// Why the launch failed, `None` when it succeeded
+  pub error: Option<String>,
+}
+
+/// Every launch, kept in `history.sqlite` in the data dir
+pub struct History {
+  db: rusqlite::Connection,
+}
+
+impl History {
+  pub fn open() -> rusqlite::Result<History> {
+    let dir = data_dir().ok_or(rusqlite::Error::InvalidPath(PathBuf::from("history.sqlite")))?;
+    if let Err(e) = fs::create_dir_all(&dir) {
+      error!(dir = %dir.display(), "failed to create the data directory for the history: {e}");
+      return Err(rusqlite::Error::InvalidPath(dir));
+    }
+    let db = rusqlite::Connection::open(dir.join("history.sqlite"))?;
+    db.execute_batch(
+      "CREATE TABLE IF NOT EXISTS launches (
+        id INTEGER PRIMARY KEY,
+        seq TEXT NOT NULL,
+        path TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        query TEXT NOT NULL,
+        launched_at INTEGER NOT NULL,
+        error TEXT
+      )"
+    )?;
+    Ok(History { db })
+  }
+  pub(crate) fn record(&self, launch: &Launch) -> rusqlite::Result<()> {
+    let kind = launch.kind.to_possible_value().unwrap();
+    self.db.execute(
+      "INSERT INTO launches (seq, path, kind, query, launched_at, error) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+      rusqlite::params![launch.seq, launch.path, kind.get_name(), launch.query, launch.launched_at, launch.error]
+    )?;
+    Ok(())
+  }
+  /// The last `limit` launches, newest first
+  pub fn recent(&self, limit: usize) -> rusqlite::Result<Vec<Launch>> {
+    let mut statement = self.db.prepare(
+      "SELECT seq, path, kind, query, launched_at, error FROM launches ORDER BY id DESC LIMIT ?1"
+    )?;
+    let launches = statement.query_map([limit as i64], |row| {
+      let kind: String = row.get(2)?;
+      Ok(Launch {
+        seq: row.get(0)?,
+        path: row.get(1)?,
+        kind: ShortcutKind::from_str(&kind, true).unwrap_or_default(),
+        query: row.get(3)?,
+        launched_at: row.get(4)?,
+        error: row.get(5)?,
+      })
+    })?;
+    launches.collect()
+  }
+  /// When each seq was last opened without failing, as a timestamp
+  pub fn last_used(&self) -> rusqlite::Result<HashMap<String, i64>> {
+    let mut statement = self.db.prepare(
+      "SELECT seq, MAX(launched_at) FROM launches WHERE error IS NULL GROUP BY seq"
+    )?;
+    let last_used = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    last_used.collect()
+  }
+  /// Seqs of the last `limit` shortcuts opened without failing, each once, newest first
+  pub fn recently_used(&self, limit: usize) -> rusqlite::Result<Vec<String>> {
+    let mut statement = self.db.prepare(
+      "SELECT seq FROM launches WHERE error IS NULL GROUP BY seq ORDER BY MAX(id) DESC LIMIT ?1"
+    )?;
+    let seqs = statement.query_map([limit as i64], |row| row.get(0))?;
+    seqs.collect()
+  }
+  /// How many times each seq was opened without failing
+  pub fn launch_counts(&self) -> rusqlite::Result<HashMap<String, i64>> {
+    let mut statement = self.db.prepare("SELECT seq, COUNT(*) FROM launches WHERE error IS NULL GROUP BY seq")?;
+    let counts = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    counts.collect()
+  }
+  pub fn clear(&self) -> rusqlite::Result<()> {
+    self.db.execute("DELETE FROM launches", [])?;
+    Ok(())
+  }
+}
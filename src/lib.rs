@@ -0,0 +1,50 @@
+//! Config loading, providers, matching, launching and the tui of bullet. The cli in `main.rs` is
+//! built on top
+
+use std::{ fs, path::{ Path, PathBuf } };
+use directories::ProjectDirs;
+use tracing::error;
+
+pub mod app;
+pub mod bench;
+pub mod clipboard;
+pub mod config;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod export;
+pub mod favorites;
+pub mod import;
+pub mod init;
+pub mod instance;
+pub mod launcher;
+pub mod providers;
+pub mod replay;
+pub mod search;
+pub mod shortcut;
+#[cfg(feature = "sync")]
+pub mod sync;
+pub mod ui;
+pub mod uri;
+
+/// Error for what bullet was built without, naming the cargo feature that brings it
+#[cfg(not(all(feature = "autotype", feature = "clipboard", feature = "network", feature = "scripting")))]
+pub(crate) fn missing_feature(feature: &str) -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::Unsupported,
+    format!("bullet was built without the {feature} feature")
+  )
+}
+
+/// Directory for logs and caches
+pub fn data_dir() -> Option<PathBuf> {
+  ProjectDirs::from("", "", "bullet").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Writes a cache file, creating its directory. One that can't be written is only logged, it is
+/// built again the next time
+pub(crate) fn write_cache(path: &Path, content: &str) {
+  let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|()| fs::write(path, content));
+  if let Err(e) = written {
+    error!(path = %path.display(), "failed to write the cache: {e}");
+  }
+}
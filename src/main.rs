@@ -1,319 +1,782 @@
-#![allow(unused_must_use, unused_variables, dead_code, warnings)]
-
-use core::fmt;
 use std::{
-  cell::RefCell,
-  fmt::{ Display, Formatter },
-  fs::File,
+  fmt::Display,
+  fs,
   io::Read,
-  path::Path,
-  process::Command,
+  net::{ TcpStream, ToSocketAddrs },
+  path::{ Path, PathBuf },
+  sync::Arc,
   thread::sleep,
   time::Duration,
 };
-use crossterm::event::{ self, poll, KeyCode, KeyEvent };
-use directories::{ BaseDirs, ProjectDirs, UserDirs };
-use ratatui::{
-  layout::{ Constraint, Layout, Rows },
-  style::{ Style, Styled, Stylize },
-  text::{ Line, Span },
-  widgets::{ Block, BorderType, Cell, Padding, Paragraph, Row, Table },
+use clap::{ Command as ClapCommand, CommandFactory, Parser, Subcommand, ValueEnum };
+use crossterm::style::{ Attribute, Color as TermColor, ContentStyle, StyledContent };
+use tracing::{ error, info, Level };
+use tracing_appender::rolling::{ RollingFileAppender, Rotation };
+use bullet_core::{
+  app::{ App, BulletError, Confirmation, collect_shortcuts },
+  bench,
+  clipboard::{ clear_clipboard, hold_clipboard, read_clipboard },
+  config::Config,
+  data_dir,
+  export::{ self, Shell },
+  import::{ self, ImportFormat },
+  init::{ self, Detected },
+  instance::{ Instance, Summon, DAEMON, PICKER },
+  launcher::{
+    compile_script,
+    find_in_path,
+    script_source,
+    DryRunLauncher,
+    History,
+  },
+  providers::build_providers,
+  replay::Replay,
+  shortcut::ShortcutKind,
+  ui::{ run_dmenu, run_tui },
+  uri::{ self, UriAction },
 };
-use serde::{ Deserialize };
-use tui_textarea::TextArea;
+#[cfg(feature = "daemon")]
+use bullet_core::daemon::DaemonRequest;
+
+/// Prints the last `limit` launches, tab separated like `list`
+fn print_history(limit: usize) -> rusqlite::Result<()> {
+  for launch in History::open()?.recent(limit)? {
+    let when = chrono::DateTime
+      ::from_timestamp(launch.launched_at, 0)
+      .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+      .unwrap_or_default();
+    let kind = launch.kind.to_possible_value().unwrap();
+    let outcome = match &launch.error {
+      Some(e) => format!("failed\t{e}"),
+      None => format!("ok\t{}", launch.path),
+    };
+    println!("{when}\t{}\t{}\t{}\t{outcome}", launch.seq, kind.get_name(), launch.query);
+  }
+  Ok(())
+}
 
-#[derive(Deserialize, Debug, Clone)]
-enum ShortcutKind {
-  #[serde(rename = "app")]
-  App,
-  #[serde(rename = "dir")]
-  Dir,
-  #[serde(rename = "file")]
-  File,
-  #[serde(rename = "url")]
-  Url,
+/// Tries a tcp connection to the host of an http(s) url
+fn check_url_reachable(url: &str) -> Result<(), String> {
+  let (scheme, rest) = url.split_once("://").ok_or("not an absolute url")?;
+  let default_port = match scheme {
+    "http" => 80,
+    "https" => 443,
+    _ => {
+      return Ok(());
+    }
+  };
+  let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+  let host_port = authority.rsplit('@').next().unwrap_or_default();
+  let address = if host_port.contains(':') {
+    host_port.to_string()
+  } else {
+    format!("{host_port}:{default_port}")
+  };
+  let socket = address
+    .to_socket_addrs()
+    .map_err(|e| e.to_string())?
+    .next()
+    .ok_or("host has no address")?;
+  TcpStream::connect_timeout(&socket, Duration::from_secs(3)).map(|_| ()).map_err(|e| e.to_string())
 }
 
-#[derive(Deserialize, Debug, Clone)]
-enum ShortcutPathPrefix {
-  #[serde(rename = "documents")]
-  Documents,
-  #[serde(rename = "appdata")]
-  Appdata,
+/// Colors text printed to stdout outside of the tui
+fn paint<D: Display>(text: D, color: TermColor) -> StyledContent<D> {
+  StyledContent::new(ContentStyle { foreground_color: Some(color), ..ContentStyle::default() }, text)
 }
 
-#[derive(Deserialize, Debug, Clone)]
-struct Shortcut {
-  path: String,
-  seq: Vec<String>,
-  description: Option<String>,
-  kind: ShortcutKind,
-  path_prefix: Option<ShortcutPathPrefix>,
+fn heading<D: Display>(text: D) -> StyledContent<D> {
+  StyledContent::new(ContentStyle { attributes: Attribute::Bold.into(), ..ContentStyle::default() }, text)
 }
 
-impl Shortcut {
-  /// Returns with prefixed path if `path_prefix` is defined, just `path` otherwise
-  fn get_prefixed_path(&self) -> String {
-    let mut path = self.path.clone();
-    if let Some(prefix) = &self.path_prefix {
-      path = Path::new(&prefix.to_string()).join(path).to_str().unwrap().to_string();
-    }
-    path
-  }
+enum DoctorStatus {
+  Ok,
+  Warn,
+  Fail,
 }
 
-impl Display for ShortcutPathPrefix {
-  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", match self {
-      ShortcutPathPrefix::Documents => {
-        let user_dirs = UserDirs::new().unwrap();
-        user_dirs.document_dir().unwrap().to_str().unwrap().to_string().replace("\\", "/")
+fn doctor_line(status: DoctorStatus, message: impl Display) {
+  let label = match status {
+    DoctorStatus::Ok => paint("ok  ", TermColor::Green),
+    DoctorStatus::Warn => paint("warn", TermColor::Yellow),
+    DoctorStatus::Fail => paint("fail", TermColor::Red),
+  };
+  println!("  {label}  {message}");
+}
+
+/// Prints the diagnostics report of `bullet doctor`, returns how many checks failed
+fn doctor() -> usize {
+  let mut failures = 0;
+  let mut warnings = 0;
+  println!("{}", heading("config"));
+  let config = match Config::path() {
+    Ok(path) => {
+      doctor_line(DoctorStatus::Ok, format!("location {}", path.display()));
+      match Config::load() {
+        Ok(config) => {
+          doctor_line(DoctorStatus::Ok, format!("parsed {} shortcuts", config.shortcuts.len()));
+          Some(config)
+        }
+        Err(e) => {
+          doctor_line(DoctorStatus::Fail, e);
+          failures += 1;
+          None
+        }
       }
-      ShortcutPathPrefix::Appdata => {
-        let base_dirs = BaseDirs::new().unwrap();
-        base_dirs.config_dir().to_str().unwrap().to_string().replace("\\", "/")
+    }
+    Err(e) => {
+      doctor_line(DoctorStatus::Fail, e);
+      failures += 1;
+      None
+    }
+  };
+
+  if let Some(config) = &config {
+    println!("{}", heading("shortcuts"));
+    for s in &config.shortcuts {
+      let seq = s.seq.first().cloned().unwrap_or_default();
+      let path = s.get_prefixed_path();
+      let check = match s.kind {
+        ShortcutKind::Dir if s.prefixed_path().is_dir() => Ok(()),
+        ShortcutKind::Dir => Err("directory does not exist".to_string()),
+        ShortcutKind::File if s.prefixed_path().is_file() => Ok(()),
+        ShortcutKind::File => Err("file does not exist".to_string()),
+        ShortcutKind::App if Path::new(&path).exists() || find_in_path(&path).is_some() => Ok(()),
+        ShortcutKind::App => Err("app is neither a path nor on PATH".to_string()),
+        ShortcutKind::Terminal if Path::new(&path).exists() || find_in_path(&path).is_some() =>
+          Ok(()),
+        ShortcutKind::Terminal => Err("command is neither a path nor on PATH".to_string()),
+        ShortcutKind::Url => match s.browser.as_ref().or(config.browser.as_ref()) {
+          Some(browser) if !Path::new(&browser.command).exists() && find_in_path(&browser.command).is_none() =>
+            Err(format!("browser {} is neither a path nor on PATH", browser.command)),
+          Some(browser) if s.incognito && browser.private_flag().is_none() =>
+            Err(format!("no private window flag is known for {}", browser.command)),
+          None if s.incognito => Err("incognito needs a browser".to_string()),
+          _ => check_url_reachable(&path),
+        },
+        ShortcutKind::Snippet if s.auto_type && !cfg!(feature = "autotype") =>
+          Err("auto_type needs bullet built with the autotype feature".to_string()),
+        ShortcutKind::Window | ShortcutKind::Snippet | ShortcutKind::Process | ShortcutKind::Note => Ok(()),
+        ShortcutKind::Secret if find_in_path(&path).is_some() => Ok(()),
+        ShortcutKind::Secret => Err("password manager is not on PATH".to_string()),
+        ShortcutKind::Script => match script_source(s) {
+          Ok(source) => compile_script(&source).map_err(|e| format!("script does not compile: {e}")),
+          Err(e) => Err(format!("script file cannot be read: {e}")),
+        },
+        ShortcutKind::Ssh if find_in_path("ssh").is_some() => Ok(()),
+        ShortcutKind::Ssh => Err("ssh is not on PATH".to_string()),
+        ShortcutKind::Session if s.items.is_empty() => Err("session has no items".to_string()),
+        ShortcutKind::Session => Ok(()),
+      };
+      match check {
+        Ok(_) => doctor_line(DoctorStatus::Ok, format!("{seq} -> {path}")),
+        Err(e) => {
+          doctor_line(DoctorStatus::Fail, format!("{seq} -> {path}: {e}"));
+          failures += 1;
+        }
       }
-    })
+      if s.seq.is_empty() {
+        doctor_line(DoctorStatus::Warn, format!("{path} has no seq and can not be typed"));
+        warnings += 1;
+      }
+      if s.count.is_some() && !matches!(s.kind, ShortcutKind::App | ShortcutKind::Terminal) {
+        doctor_line(DoctorStatus::Warn, format!("{seq} -> {path}: count only applies to app and terminal shortcuts"));
+        warnings += 1;
+      }
+      if let Some(color) = s.color.as_ref().filter(|color| color.parse::<ratatui::style::Color>().is_err()) {
+        doctor_line(DoctorStatus::Warn, format!("{seq} -> {path}: color \"{color}\" is neither a colour name nor a hex code"));
+        warnings += 1;
+      }
+      if s.open.is_some() && s.kind != ShortcutKind::File {
+        doctor_line(DoctorStatus::Warn, format!("{seq} -> {path}: open only applies to file shortcuts"));
+        warnings += 1;
+      }
+    }
+    for (kind, action) in &config.default_actions {
+      if !action.applies_to(*kind) {
+        let kind = kind.to_possible_value().unwrap();
+        doctor_line(
+          DoctorStatus::Warn,
+          format!("default action \"{}\" does not apply to {} shortcuts, they are opened", action.label(), kind.get_name())
+        );
+        warnings += 1;
+      }
+    }
   }
+
+  println!("{}", heading("handlers"));
+  let url_handler = if cfg!(windows) {
+    Some("explorer".to_string())
+  } else if cfg!(target_os = "macos") {
+    find_in_path("open").map(|_| "open".to_string())
+  } else {
+    ["xdg-open", "gio", "wslview"]
+      .iter()
+      .find(|program| find_in_path(program).is_some())
+      .map(|program| program.to_string())
+  };
+  match url_handler {
+    Some(handler) => doctor_line(DoctorStatus::Ok, format!("urls open with {handler}")),
+    None => {
+      doctor_line(DoctorStatus::Fail, "no url handler found (xdg-open, gio or wslview)");
+      failures += 1;
+    }
+  }
+  let terminal = config.as_ref().map(|config| config.terminal.clone()).unwrap_or_default();
+  match terminal.emulator() {
+    Some(emulator) if Path::new(&emulator.program).exists() || find_in_path(&emulator.program).is_some() =>
+      doctor_line(DoctorStatus::Ok, format!("terminal is {}", emulator.program)),
+    Some(emulator) => {
+      doctor_line(DoctorStatus::Fail, format!("terminal {} is neither a path nor on PATH", emulator.program));
+      failures += 1;
+    }
+    None if cfg!(windows) => doctor_line(DoctorStatus::Ok, "terminal is cmd"),
+    None if cfg!(target_os = "macos") => doctor_line(DoctorStatus::Ok, "terminal is Terminal"),
+    None if find_in_path("x-terminal-emulator").is_some() =>
+      doctor_line(DoctorStatus::Ok, "terminal is x-terminal-emulator"),
+    None => {
+      doctor_line(DoctorStatus::Warn, "no terminal found, set `terminal` in the config or $TERMINAL");
+      warnings += 1;
+    }
+  }
+  match ["VISUAL", "EDITOR"].iter().find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty())) {
+    Some(program) => doctor_line(DoctorStatus::Ok, format!("editor is {program}")),
+    None => {
+      doctor_line(DoctorStatus::Warn, "no editor configured, set $VISUAL");
+      warnings += 1;
+    }
+  }
+
+  println!();
+  let summary = format!("{failures} failed, {warnings} warnings");
+  if failures > 0 {
+    println!("{}", paint(summary, TermColor::Red));
+  } else if warnings > 0 {
+    println!("{}", paint(summary, TermColor::Yellow));
+  } else {
+    println!("{}", paint(summary, TermColor::Green));
+  }
+  failures
 }
 
-#[derive(Deserialize, Debug)]
-struct Config {
-  shortcuts: Vec<Shortcut>,
+#[derive(Parser)]
+#[command(name = "bullet", version, about = "Open apps, folders, files and urls by typing short sequences")]
+struct Cli {
+  /// Sequence of a shortcut to open right away, without showing the picker
+  seq: Option<String>,
+  /// Only show and open shortcuts of this kind
+  #[arg(long, global = true)]
+  kind: Option<ShortcutKind>,
+  /// Pick one of the lines read from stdin and print it to stdout, like dmenu
+  #[arg(long)]
+  dmenu: bool,
+  /// Open the picker with this typed into the search. Handed over to the picker when one is open
+  #[arg(long, conflicts_with = "seq")]
+  query: Option<String>,
+  /// Log debug details, like every search and its matches, to the log file
+  #[arg(short, long, global = true)]
+  verbose: bool,
+  /// Print errors to stderr as JSON objects instead of plain text
+  #[arg(long, global = true)]
+  json_errors: bool,
+  /// Print what would be opened instead of opening it
+  #[arg(long, global = true)]
+  dry_run: bool,
+  /// Type the keys of a script into the picker before the keyboard, for demos. Its lines are steps
+  /// like `type notes`, `key ctrl+y`, `wait 500` or `delay 80`, the pause after every key
+  #[arg(long, conflicts_with_all = ["seq", "dmenu"])]
+  replay: Option<PathBuf>,
+  #[command(subcommand)]
+  command: Option<CliCommand>,
 }
 
-trait ShortcutsTrait {
-  fn find(&self, search: String) -> Vec<Shortcut>;
+#[derive(Subcommand)]
+enum CliCommand {
+  /// Print a completion script for the given shell
+  Completions {
+    shell: Shell,
+  },
+  /// Open the shortcut of a seq like `bullet <seq>`, passing what comes after `--` to its app or
+  /// terminal command, as in `bullet run edit -- notes.txt`
+  Run {
+    seq: String,
+    /// Arguments added after the shortcut's own
+    #[arg(last = true)]
+    args: Vec<String>,
+  },
+  /// Write a first config, asking where it goes and for a few shortcuts, with the browser, terminal
+  /// and editor found on this system
+  Init,
+  /// Print the shortcuts, one per line with their kind and path
+  List,
+  /// Check the config and every shortcut target, and print a report
+  Doctor,
+  /// Print every shortcut sequence, one per line. Used by the completion scripts
+  #[command(name = "__seqs", hide = true)]
+  Seqs,
+  /// Collect the shortcuts of every provider again and update their caches
+  Refresh,
+  /// Print the shortcuts of another launcher's export as the shortcuts of the config
+  Import {
+    format: ImportFormat,
+    /// The exported file
+    file: PathBuf,
+  },
+  /// Write the URL shortcuts of the config as a bookmarks file browsers import, or every shortcut as
+  /// an alias of the given shell
+  Export {
+    /// As a Netscape bookmarks HTML file
+    #[arg(long, required_unless_present = "aliases", conflicts_with = "aliases")]
+    bookmarks: bool,
+    /// As aliases to source in the shell's profile, `cd`ing into dirs and running `bullet run <seq>`
+    /// for the rest
+    #[arg(long, value_name = "SHELL")]
+    aliases: Option<Shell>,
+    /// File to write instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+  },
+  /// Open `bullet://open?seq=<seq>` links, or register bullet as their handler
+  Uri {
+    #[command(subcommand)]
+    command: UriCommand,
+  },
+  /// Stay running and open the picker in a new terminal window when the global hotkey is pressed
+  #[cfg(feature = "daemon")]
+  Daemon {
+    /// Hotkey to listen for instead of the config's `daemon.hotkey`, like `ctrl+alt+KeyB`
+    #[arg(long)]
+    hotkey: Option<String>,
+  },
+  /// Commit the changes to the config to a git repository in its directory, and exchange them with
+  /// the config's `sync.remote`
+  #[cfg(feature = "sync")]
+  Sync,
+  /// Show the last launches, or manage the history
+  History {
+    #[command(subcommand)]
+    command: Option<HistoryCommand>,
+  },
+  /// Time parsing the config, starting up and matching each keystroke with a synthetic config
+  Bench {
+    /// How many shortcuts the synthetic config has
+    #[arg(short = 'n', long, default_value_t = 10_000)]
+    shortcuts: usize,
+  },
+  /// Clear the clipboard after the given seconds if it still holds what is read from stdin
+  #[command(name = "__clear-clipboard", hide = true)]
+  ClearClipboard {
+    seconds: u64,
+  },
+  /// Copy what is read from stdin and keep serving it until something else is copied, the clipboards
+  /// of X11 and Wayland only hold what a running process does
+  #[command(name = "__hold-clipboard", hide = true)]
+  HoldClipboard,
 }
 
-impl ShortcutsTrait for Vec<Shortcut> {
-  fn find(&self, search: String) -> Self {
-    if search.trim().is_empty() {
-      return self.to_vec();
-    }
-    self
-      .iter()
-      .filter(|s| s.seq.iter().any(|seq| seq.contains(&search)))
-      .map(|seq| seq.clone())
-      .collect()
-  }
+#[derive(Subcommand)]
+enum HistoryCommand {
+  /// Print the last launches with when, from which query and whether they failed. The default
+  List {
+    /// How many launches to print
+    #[arg(short = 'n', long, default_value_t = 20)]
+    limit: usize,
+  },
+  /// Forget every recorded launch
+  Clear,
 }
 
-#[derive(Debug)]
-enum LoadConfigError {
-  IoError(std::io::Error),
-  ParseError(serde_json::Error),
-  NoConfig,
+#[derive(Subcommand)]
+enum UriCommand {
+  /// Register bullet as the handler of bullet:// links for the current user
+  Install,
+  /// Open the shortcut a link names, through the daemon when it runs
+  Open {
+    uri: String,
+  },
 }
 
-struct App {
-  config: Result<Config, LoadConfigError>,
-  matched_shortcuts: Vec<Shortcut>,
-  running: bool,
+/// Words completed right after `bullet`, without the dynamic shortcut sequences
+fn root_completion_words(cmd: &ClapCommand) -> Vec<String> {
+  let mut words: Vec<String> = cmd
+    .get_subcommands()
+    .filter(|c| !c.is_hide_set())
+    .map(|c| c.get_name().to_string())
+    .collect();
+  words.extend(flag_completion_words(cmd));
+  words
 }
 
-impl App {
-  fn new() -> Self {
-    let mut app = App {
-      running: true,
-      config: App::load_config(),
-      matched_shortcuts: vec![],
-    };
-    if let Ok(config) = &app.config {
-      app.matched_shortcuts = config.shortcuts.clone();
+fn flag_completion_words(cmd: &ClapCommand) -> Vec<String> {
+  let mut words = vec![];
+  for arg in cmd.get_arguments().filter(|a| !a.is_positional() && !a.is_hide_set()) {
+    if let Some(long) = arg.get_long() {
+      words.push(format!("--{long}"));
+    }
+    if let Some(short) = arg.get_short() {
+      words.push(format!("-{short}"));
     }
-    app
-  }
-  fn load_config() -> Result<Config, LoadConfigError> {
-    let config_path = UserDirs::new().map(|user_dirs|
-      user_dirs.document_dir().unwrap().join("bullet/config.json").to_str().unwrap().to_string()
-    );
-    if config_path.is_none() {
-      return Err(LoadConfigError::NoConfig);
-    }
-    let config_file = File::open(config_path.unwrap());
-    if config_file.is_err() {
-      return Err(LoadConfigError::IoError(config_file.err().unwrap()));
-    }
-    let mut content = String::new();
-    config_file.map(|mut f| f.read_to_string(&mut content));
-    let config = serde_json::from_str::<Config>(&content);
-    config.map_err(|e| LoadConfigError::ParseError(e))
   }
-  fn find_and_handle_matches(&mut self, search: String) {
-    if let Ok(cfg) = &self.config {
-      self.matched_shortcuts = cfg.shortcuts.find(search.clone());
-    }
-    let path: Option<String> = {
-      if self.matched_shortcuts.len() == 1 {
-        Some(self.matched_shortcuts[0].get_prefixed_path())
-      } else {
-        self.matched_shortcuts
-          .iter()
-          .find(|s| s.seq.iter().any(|seq| *seq == search))
-          .map(|s| s.get_prefixed_path())
+  words
+}
+
+/// Nested subcommands, flags and possible positional values of every visible subcommand
+fn subcommand_completion_words(cmd: &ClapCommand) -> Vec<(String, Vec<String>)> {
+  cmd
+    .get_subcommands()
+    .filter(|c| !c.is_hide_set())
+    .map(|sub| {
+      let mut words: Vec<String> = sub
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .map(|c| c.get_name().to_string())
+        .collect();
+      words.extend(
+        sub
+          .get_arguments()
+          .filter(|a| a.is_positional())
+          .flat_map(|a| a.get_possible_values())
+          .map(|v| v.get_name().to_string())
+      );
+      words.extend(flag_completion_words(sub));
+      (sub.get_name().to_string(), words)
+    })
+    .filter(|(_, words)| !words.is_empty())
+    .collect()
+}
+
+fn completion_script(shell: Shell) -> String {
+  let mut cmd = Cli::command();
+  cmd.build();
+  let root = root_completion_words(&cmd).join(" ");
+  let subs = subcommand_completion_words(&cmd);
+  match shell {
+    Shell::Bash => {
+      let cases: String = subs
+        .iter()
+        .map(|(name, words)| {
+          format!(
+            "    {name}) COMPREPLY=( $(compgen -W \"{}\" -- \"$cur\") ) ;;\n",
+            words.join(" ")
+          )
+        })
+        .collect();
+      format!(
+        "_bullet() {{\n  local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n  if [[ $COMP_CWORD -eq 1 ]]; then\n    COMPREPLY=( $(compgen -W \"{root} $(bullet __seqs 2>/dev/null)\" -- \"$cur\") )\n    return\n  fi\n  case \"${{COMP_WORDS[1]}}\" in\n{cases}  esac\n}}\ncomplete -F _bullet bullet\n"
+      )
+    }
+    Shell::Zsh => {
+      let cases: String = subs
+        .iter()
+        .map(|(name, words)| format!("    {name}) compadd -- {} ;;\n", words.join(" ")))
+        .collect();
+      format!(
+        "#compdef bullet\n_bullet() {{\n  if (( CURRENT == 2 )); then\n    local -a items\n    items=({root} ${{(f)\"$(bullet __seqs 2>/dev/null)\"}})\n    compadd -a items\n    return\n  fi\n  case \"${{words[2]}}\" in\n{cases}  esac\n}}\ncompdef _bullet bullet\n"
+      )
+    }
+    Shell::Fish => {
+      let mut script = String::from("complete -c bullet -f\n");
+      for sub in cmd.get_subcommands().filter(|c| !c.is_hide_set()) {
+        script.push_str(
+          &format!(
+            "complete -c bullet -n __fish_use_subcommand -a {} -d '{}'\n",
+            sub.get_name(),
+            sub.get_about().map(|a| a.to_string()).unwrap_or_default().replace("'", "\\'")
+          )
+        );
       }
-    };
-    if let Some(p) = path {
-      let shortcut_res = open::that_detached(p);
-      match shortcut_res {
-        Ok(_) => {
-          self.running = false;
-        }
-        Err(_) => {}
+      for flag in flag_completion_words(&cmd) {
+        let option = match flag.strip_prefix("--") {
+          Some(long) => format!("-l {long}"),
+          None => format!("-s {}", flag.trim_start_matches('-')),
+        };
+        script.push_str(&format!("complete -c bullet -n __fish_use_subcommand {option}\n"));
+      }
+      script.push_str(
+        "complete -c bullet -n __fish_use_subcommand -a '(bullet __seqs 2>/dev/null)' -d shortcut\n"
+      );
+      for (name, words) in &subs {
+        script.push_str(
+          &format!(
+            "complete -c bullet -n '__fish_seen_subcommand_from {name}' -a '{}'\n",
+            words.join(" ")
+          )
+        );
       }
+      script
+    }
+    Shell::Powershell => {
+      let quote = |words: &Vec<String>| {
+        words
+          .iter()
+          .map(|w| format!("'{w}'"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      };
+      let cases: String = subs
+        .iter()
+        .map(|(name, words)| format!("      '{name}' {{ $candidates = @({}) }}\n", quote(words)))
+        .collect();
+      format!(
+        "Register-ArgumentCompleter -Native -CommandName bullet -ScriptBlock {{\n  param($wordToComplete, $commandAst, $cursorPosition)\n  $words = @($commandAst.CommandElements | ForEach-Object {{ $_.ToString() }})\n  $candidates = @()\n  if ($words.Count -eq 1 -or ($words.Count -eq 2 -and $wordToComplete)) {{\n    $candidates = @({}) + @(bullet __seqs 2>$null)\n  }} else {{\n    switch ($words[1]) {{\n{cases}    }}\n  }}\n  $candidates | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{\n    [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)\n  }}\n}}\n",
+        quote(&root_completion_words(&cmd))
+      )
     }
   }
 }
 
-fn main() {
-  let mut app = App::new();
-  let mut term = ratatui::init();
-
-  let mut search_input = TextArea::default();
-  search_input.set_block(
-    Block::bordered()
-      .border_type(BorderType::Rounded)
-      .border_style(Style::new().dark_gray())
-      .padding(Padding::horizontal(1))
-  );
+/// Logs into a daily rotated file in the data dir, keeping a week of files
+fn init_logging(verbose: bool) {
+  let Some(dir) = data_dir() else {
+    return;
+  };
+  // without the directory there is nowhere to log to
+  if fs::create_dir_all(&dir).is_err() {
+    return;
+  }
+  let appender = RollingFileAppender::builder()
+    .rotation(Rotation::DAILY)
+    .filename_prefix("bullet")
+    .filename_suffix("log")
+    .max_log_files(7)
+    .build(dir);
+  if let Ok(appender) = appender {
+    tracing_subscriber
+      ::fmt()
+      .with_writer(appender)
+      .with_ansi(false)
+      .with_max_level(if verbose { Level::DEBUG } else { Level::INFO })
+      .init();
+  }
+}
 
-  loop {
-    if !app.running {
-      break;
+fn main() {
+  let cli = Cli::parse();
+  init_logging(cli.verbose);
+  let json_errors = cli.json_errors;
+  if let Err(e) = run(cli) {
+    match e {
+      BulletError::Cancelled => info!("cancelled by user"),
+      _ => error!(kind = e.kind(), "{e}"),
     }
-    term.draw(|frame| {
-      let layout = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]);
-      let [search_area, main_area] = layout.areas(frame.area());
+    e.report(json_errors);
+    std::process::exit(e.exit_code());
+  }
+}
 
-      let (matched_apps, matched_dirs, matched_files, matched_urls) = {
-        let mut apps: Vec<Shortcut> = vec![];
-        let mut urls: Vec<Shortcut> = vec![];
-        let mut dirs: Vec<Shortcut> = vec![];
-        let mut files: Vec<Shortcut> = vec![];
-        for s in &app.matched_shortcuts {
-          match s.kind {
-            ShortcutKind::App => apps.push(s.clone()),
-            ShortcutKind::File => files.push(s.clone()),
-            ShortcutKind::Dir => dirs.push(s.clone()),
-            ShortcutKind::Url => urls.push(s.clone()),
-          }
-        }
-        (apps, dirs, files, urls)
+fn run(cli: Cli) -> Result<(), BulletError> {
+  match cli.command {
+    Some(CliCommand::Completions { shell }) => {
+      print!("{}", completion_script(shell));
+      Ok(())
+    }
+    Some(CliCommand::ClearClipboard { seconds }) => {
+      let mut text = String::new();
+      std::io::stdin().read_to_string(&mut text).map_err(|e| BulletError::Io("read the copied text".to_string(), e))?;
+      sleep(Duration::from_secs(seconds));
+      if read_clipboard().is_ok_and(|current| current == text.trim_end_matches(['\r', '\n'])) {
+        clear_clipboard().map_err(|e| BulletError::Io("clear the clipboard".to_string(), e))?;
+      }
+      Ok(())
+    }
+    Some(CliCommand::HoldClipboard) => {
+      let mut text = String::new();
+      std::io::stdin().read_to_string(&mut text).map_err(|e| BulletError::Io("read the copied text".to_string(), e))?;
+      hold_clipboard(&text).map_err(|e| BulletError::Io("hold the clipboard".to_string(), e))?;
+      Ok(())
+    }
+    Some(CliCommand::Doctor) => {
+      match doctor() {
+        0 => Ok(()),
+        failures => Err(BulletError::DoctorFailed(failures)),
+      }
+    }
+    Some(CliCommand::Run { seq, args }) => {
+      let dry_run = Arc::new(DryRunLauncher::default());
+      let mut app = App::new(cli.kind).with_extra_args(args);
+      if cli.dry_run {
+        app = app.with_launcher(dry_run.clone());
+      }
+      let result = open_seq(app, seq);
+      for opened in dry_run.opened() {
+        println!("dry run: {opened}");
+      }
+      result
+    }
+    Some(CliCommand::List) => {
+      let mut app = App::new(cli.kind);
+      if let Err(e) = app.config {
+        return Err(BulletError::Config(e));
+      }
+      app.wait_for_shortcuts();
+      for s in app.shortcuts() {
+        let kind = s.kind.to_possible_value().unwrap();
+        println!("{}\t{}\t{}", s.seq.join(","), kind.get_name(), s.get_prefixed_path());
+      }
+      Ok(())
+    }
+    #[cfg(feature = "daemon")]
+    Some(CliCommand::Daemon { hotkey }) => bullet_core::daemon::run(hotkey),
+    Some(CliCommand::Uri { command: UriCommand::Install }) => {
+      uri::install().map_err(BulletError::UriInstall)?;
+      println!("bullet:// links now open with {}", std::env::current_exe().unwrap_or_default().display());
+      Ok(())
+    }
+    Some(CliCommand::Uri { command: UriCommand::Open { uri } }) => {
+      let UriAction::Open(seq) = UriAction::parse(&uri).map_err(BulletError::InvalidUri)?;
+      #[cfg(feature = "daemon")]
+      match Instance::send(DAEMON, &DaemonRequest::Open(seq.clone())) {
+        Ok(()) => return Ok(()),
+        Err(e) => info!("no daemon to open the link, opening it here: {e}"),
+      }
+      let mut app = App::new(None);
+      if let Err(e) = app.config {
+        return Err(BulletError::Config(e));
+      }
+      app.wait_for_shortcuts();
+      app.open_linked(&seq)
+    }
+    Some(CliCommand::Refresh) => {
+      let config = Config::load().map_err(BulletError::Config)?;
+      for provider in build_providers(&config) {
+        let shortcuts = collect_shortcuts(provider.as_ref());
+        println!("{}\t{}", provider.name(), shortcuts.len());
+      }
+      Ok(())
+    }
+    Some(CliCommand::Import { format, file }) => {
+      let content = fs::read_to_string(&file).map_err(|e| BulletError::Io(format!("read {}", file.display()), e))?;
+      let shortcuts = import::import(format, &content).map_err(|e| BulletError::Parse(file.display().to_string(), e))?;
+      println!("{}", serde_json::to_string_pretty(&shortcuts).unwrap());
+      let plural = if shortcuts.len() == 1 { "" } else { "s" };
+      eprintln!("bullet: imported {} shortcut{plural}, add them to the shortcuts of the config", shortcuts.len());
+      Ok(())
+    }
+    #[cfg(feature = "sync")]
+    Some(CliCommand::Sync) => {
+      let config = Config::load().map_err(BulletError::Config)?;
+      for done in bullet_core::sync::run(&config)? {
+        println!("{done}");
+      }
+      Ok(())
+    }
+    Some(CliCommand::Export { aliases, output, .. }) => {
+      let config = Config::load().map_err(BulletError::Config)?;
+      let exported = match aliases {
+        Some(shell) => export::shell_aliases(&config.shortcuts, shell),
+        None => export::bookmarks_html(&config.shortcuts),
       };
-
-      let mut table_rows: Vec<Row> = Vec::new();
-      for shortcuts in [matched_apps, matched_dirs, matched_files, matched_urls] {
-        if shortcuts.len() > 0 {
-          for s in shortcuts {
-            let seq = s.seq[0].clone();
-            match s.kind {
-              ShortcutKind::App => {
-                let desc = &s.description.unwrap_or("".to_string());
-                let cells = vec![
-                  Cell::new(
-                    Line::from(vec![Span::from(">__ ").red(), Span::from(seq).bold().light_red()])
-                  ),
-                  Cell::new(desc.clone())
-                ];
-                table_rows.push(Row::new(cells));
-              }
-              ShortcutKind::Dir => {
-                let path = s.path.clone();
-                let prefix = s.path_prefix.map(|p| p.to_string());
-                let cells = vec![
-                  Cell::new(
-                    Line::from(
-                      vec![Span::from("[_] ").green(), Span::from(seq).bold().light_green()]
-                    )
-                  ),
-                  Cell::new(
-                    Line::from({
-                      let mut spans = vec![];
-                      if let Some(p) = prefix {
-                        spans.push(Span::from(p).underlined());
-                        spans.push(Span::from("/").underlined());
-                      }
-                      spans.push(Span::from(path));
-                      spans
-                    })
-                  )
-                ];
-                table_rows.push(Row::new(cells));
-              }
-              ShortcutKind::File => {
-                let path = s.path.clone();
-                let prefix = s.path_prefix.map(|p| p.to_string());
-                let cells = vec![
-                  Cell::new(
-                    Line::from(
-                      vec![Span::from("[_] ").yellow(), Span::from(seq).bold().light_yellow()]
-                    )
-                  ),
-                  Cell::new(
-                    Line::from({
-                      let mut spans = vec![];
-                      if let Some(p) = prefix {
-                        spans.push(Span::from(p).underlined());
-                        spans.push(Span::from("/").underlined());
-                      }
-                      spans.push(Span::from(path));
-                      spans
-                    })
-                  )
-                ];
-                table_rows.push(Row::new(cells));
-              }
-              ShortcutKind::Url => {
-                let desc = s.description.unwrap_or_default();
-                let cells = vec![
-                  Cell::new(
-                    Line::from(vec![Span::from("(#) ").blue(), Span::from(seq).bold().light_blue()])
-                  ),
-                  Cell::new(desc)
-                ];
-                table_rows.push(Row::new(cells));
-              }
-            }
-          }
-        }
+      match output {
+        Some(output) =>
+          fs::write(&output, exported).map_err(|e| BulletError::Io(format!("write {}", output.display()), e))?,
+        None => print!("{exported}"),
       }
-      let mut shortcuts_table = Table::new(
-        table_rows,
-        vec![Constraint::Length(8), Constraint::Fill(1)]
-      ).column_spacing(1);
-
-      frame.render_widget(&search_input, search_area);
-      match &app.config {
-        Ok(_) => {
-          frame.render_widget(&shortcuts_table, main_area);
-        }
-        Err(e) => {
-          let error_p = Paragraph::new(match e {
-            LoadConfigError::IoError(e) => e.to_string(),
-            LoadConfigError::ParseError(e) => e.to_string(),
-            LoadConfigError::NoConfig =>
-              "Config does not exist in \"documents/bullet/config.json\"".to_string(),
-          });
-          frame.render_widget(&error_p, main_area);
-        }
+      Ok(())
+    }
+    Some(CliCommand::History { command }) => {
+      let result = match command.unwrap_or(HistoryCommand::List { limit: 20 }) {
+        HistoryCommand::List { limit } => print_history(limit),
+        HistoryCommand::Clear => History::open().and_then(|history| history.clear()),
+      };
+      result.map_err(|e| BulletError::History(e.to_string()))
+    }
+    Some(CliCommand::Init) => {
+      init::run(&mut std::io::stdin().lock(), &mut std::io::stdout(), &Detected::detect())
+        .map_err(|e| BulletError::Io("write the config".to_string(), e))?;
+      Ok(())
+    }
+    Some(CliCommand::Bench { shortcuts }) => {
+      let report = bench::run(shortcuts).map_err(|e| BulletError::Parse("the synthetic config".to_string(), e.to_string()))?;
+      println!("{report}");
+      Ok(())
+    }
+    Some(CliCommand::Seqs) => {
+      let mut app = App::new(cli.kind);
+      if let Err(e) = app.config {
+        return Err(BulletError::Config(e));
       }
-    });
-    if poll(Duration::from_millis(100)).unwrap() {
-      if let event::Event::Key(key_event) = event::read().unwrap() {
-        match key_event.code {
-          KeyCode::Esc => {
-            app.running = false;
+      app.wait_for_shortcuts();
+      for seq in app.shortcuts().iter().flat_map(|s| s.seq.iter()) {
+        println!("{seq}");
+      }
+      Ok(())
+    }
+    None if cli.dmenu => {
+      let candidates: Vec<String> = std::io::stdin().lines().map_while(Result::ok).collect();
+      let chosen = run_dmenu(candidates)?;
+      println!("{chosen}");
+      Ok(())
+    }
+    None => {
+      let dry_run = Arc::new(DryRunLauncher::default());
+      let mut app = App::new(cli.kind);
+      if cli.dry_run {
+        app = app.with_launcher(dry_run.clone());
+      }
+      let result = match cli.seq {
+        Some(seq) => open_seq(app, seq),
+        None => {
+          let summon = Summon { query: cli.query };
+          match Instance::claim(PICKER) {
+            Ok(Some(instance)) => app = app.with_instance(instance),
+            Ok(None) => match Instance::send(PICKER, &summon) {
+              Ok(()) => return Ok(()),
+              Err(e) => error!("failed to summon the open picker, opening another: {e}"),
+            },
+            Err(e) => error!("failed to check for an open picker, opening another: {e}"),
           }
-          _ => {
-            search_input.input(key_event);
-            let search = search_input.lines()[0].clone();
-            app.find_and_handle_matches(search);
+          if let Some(query) = &summon.query {
+            app.set_search(query);
           }
+          if let Some(path) = &cli.replay {
+            let replay = Replay::load(path).map_err(|e| match e.kind() {
+              std::io::ErrorKind::InvalidData => BulletError::Parse(format!("the replay {}", path.display()), e.to_string()),
+              _ => BulletError::Io(format!("read the replay {}", path.display()), e),
+            })?;
+            app = app.with_replay(replay);
+          }
+          run_tui(app)
         }
+      };
+      // printed once the tui is gone, it would be drawn over otherwise
+      for opened in dry_run.opened() {
+        println!("dry run: {opened}");
       }
+      result
     }
   }
+}
 
-  ratatui::restore();
+/// Opens the shortcut of `seq` without the picker, asking on the terminal for a confirmation or the
+/// values of a snippet
+fn open_seq(mut app: App, seq: String) -> Result<(), BulletError> {
+  if let Err(e) = app.config {
+    return Err(BulletError::Config(e));
+  }
+  app.wait_for_shortcuts();
+  match app.find_and_handle_matches(seq.clone()) {
+    Err(BulletError::NoMatch(_)) => app.open_result(&seq)?,
+    result => result?,
+  }
+  if let Some(Confirmation::Open(shortcut)) = app.pending_confirmation.take() {
+    eprint!("bullet: open \"{}\"? [y/N] ", shortcut.name());
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).map_err(|e| BulletError::Io("read the answer".to_string(), e))?;
+    if !answer.trim().eq_ignore_ascii_case("y") {
+      return Err(BulletError::Cancelled);
+    }
+    app.open_confirmed(&shortcut)?;
+  }
+  match app.snippet_prompt.take() {
+    Some(mut prompt) => {
+      for name in prompt.names.clone() {
+        eprint!("bullet: {name}: ");
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).map_err(|e| BulletError::Io("read the answer".to_string(), e))?;
+        prompt.answers.push(answer.trim_end_matches(['\r', '\n']).to_string());
+      }
+      app.open_answered(&prompt.filled())
+    }
+    None => Ok(()),
+  }
 }
@@ -0,0 +1,2559 @@
+//! Where shortcuts come from besides the config, each provider behind a flag in `providers`
+
+use std::{
+  collections::BTreeMap,
+  fs,
+  path::{ Path, PathBuf },
+  thread::sleep,
+  time::Duration,
+};
+#[cfg(feature = "providers")]
+use std::{ io::Write, process::{ Command, Stdio } };
+#[cfg(feature = "providers")]
+use directories::BaseDirs;
+use serde::{ Deserialize, Serialize };
+use tracing::error;
+#[cfg(feature = "providers")]
+use tracing::debug;
+use crate::{
+  config::{ Config, CurrencyConfig },
+  data_dir,
+  shortcut::{ fill_url, Shortcut, ShortcutKind },
+  write_cache,
+};
+#[cfg(feature = "providers")]
+use crate::config::{
+  ExternalProviderConfig,
+  FileIndexConfig,
+  GitReposConfig,
+  PasswordManager,
+  PasswordsConfig,
+};
+
+/// A source of shortcuts. The shortcuts written in the config are one provider,
+/// others discover them on the system. Each runs on its own worker thread, see `spawn_provider_worker`
+pub trait Provider: Send + Sync {
+  /// Name shown in logs
+  fn name(&self) -> &'static str;
+  fn shortcuts(&self) -> Vec<Shortcut>;
+  /// Query prefix, like `$`, the shortcuts are searched behind instead of with everything else
+  fn prefix(&self) -> Option<&'static str> {
+    None
+  }
+  /// Shortcuts made for the current query, like creating something named after it.
+  /// They are only opened with Enter, never right away
+  fn search(&self, _query: &str) -> Vec<Shortcut> {
+    vec![]
+  }
+  /// Whether `search` has new results since it was last asked, for providers fetching them in the background
+  fn updated(&self) -> bool {
+    false
+  }
+  /// Whether collecting the shortcuts is slow enough to keep the last ones in the data dir,
+  /// so they show up right away on the next start while they are collected again
+  fn cached(&self) -> bool {
+    false
+  }
+  /// Whether results are being fetched in the background, or fetched but not asked for with `updated` yet.
+  /// The ui only wakes up without input while a provider is pending
+  fn pending(&self) -> bool {
+    false
+  }
+}
+
+/// Shortcuts written by hand in the config
+struct ConfigProvider {
+  shortcuts: Vec<Shortcut>,
+}
+
+impl Provider for ConfigProvider {
+  fn name(&self) -> &'static str {
+    "config"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    self.shortcuts.clone()
+  }
+}
+
+/// Body of the response to a GET request
+#[cfg(feature = "network")]
+fn http_get(url: &str) -> Result<String, String> {
+  ureq
+    ::get(url)
+    .call()
+    .map_err(|e| e.to_string())?
+    .body_mut()
+    .read_to_string()
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "network"))]
+fn http_get(_url: &str) -> Result<String, String> {
+  Err(crate::missing_feature("network").to_string())
+}
+
+/// Suggestions of the last searched query, fetched in the background
+#[derive(Default)]
+struct WebSuggestions {
+  /// Query the suggestions are wanted for, a fetch for an older one is dropped
+  latest: std::sync::Arc<std::sync::Mutex<String>>,
+  /// Query and its suggestions
+  fetched: std::sync::Arc<std::sync::Mutex<(String, Vec<String>)>>,
+  updated: std::sync::Arc<std::sync::atomic::AtomicBool>,
+  /// Fetches that have not finished
+  in_flight: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl WebSuggestions {
+  /// Reads `["query", ["suggestion", ...]]`
+  fn fetch(url: &str) -> Result<Vec<String>, String> {
+    let body = http_get(url)?;
+    let response = serde_json::from_str::<serde_json::Value>(&body).map_err(|e| e.to_string())?;
+    Ok(
+      response[1]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.as_str().map(String::from))
+        .collect()
+    )
+  }
+  /// Suggestions for `query` if they are fetched, otherwise a fetch is started
+  fn get(&self, suggest_url: &str, query: &str) -> Vec<String> {
+    let (fetched_query, suggestions) = self.fetched.lock().unwrap().clone();
+    if fetched_query == query {
+      return suggestions;
+    }
+    let mut latest = self.latest.lock().unwrap();
+    if *latest != query {
+      *latest = query.to_string();
+      let (latest, fetched, updated) = (self.latest.clone(), self.fetched.clone(), self.updated.clone());
+      let in_flight = self.in_flight.clone();
+      let (url, query) = (fill_url(suggest_url, "query", query), query.to_string());
+      in_flight.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+      std::thread::spawn(move || {
+        // waits for typing to settle, so not every keystroke is fetched
+        sleep(Duration::from_millis(150));
+        if *latest.lock().unwrap() == query {
+          match WebSuggestions::fetch(&url) {
+            Ok(suggestions) => {
+              *fetched.lock().unwrap() = (query, suggestions);
+              updated.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+            Err(e) => error!(url, "failed to fetch suggestions: {e}"),
+          }
+        }
+        in_flight.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+      });
+    }
+    vec![]
+  }
+}
+
+/// What is typed after one of the seqs of `shortcut` and a space, if anything is
+fn typed_after_seq<'a>(shortcut: &Shortcut, query: &'a str) -> Option<&'a str> {
+  shortcut.seq
+    .iter()
+    .find_map(|seq| query.strip_prefix(seq.as_str())?.strip_prefix(' '))
+    .map(str::trim)
+    .filter(|q| !q.is_empty())
+}
+
+/// Searches with the config's search URLs, typed as `<seq> <query>`, along with the engine's suggestions
+struct WebSearchProvider {
+  searches: Vec<Shortcut>,
+  /// Only set with `web_suggestions`, since it makes network calls while typing
+  suggestions: Option<WebSuggestions>,
+}
+
+impl WebSearchProvider {
+  fn shortcut(search: &Shortcut, query: &str, description: String) -> Shortcut {
+    Shortcut {
+      path: fill_url(&search.path, "query", query),
+      seq: vec![format!("{} {query}", search.name())],
+      description: Some(description),
+      kind: ShortcutKind::Url,
+      tags: vec!["search".to_string()],
+      browser: search.browser.clone(),
+      incognito: search.incognito,
+      ..Shortcut::default()
+    }
+  }
+}
+
+impl Provider for WebSearchProvider {
+  fn name(&self) -> &'static str {
+    "web_search"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    for search in &self.searches {
+      let Some(typed) = typed_after_seq(search, query) else {
+        continue;
+      };
+      let engine = search.description.clone().unwrap_or(search.name().to_string());
+      shortcuts.push(Self::shortcut(search, typed, format!("search {engine} for {typed}")));
+      if let (Some(suggestions), Some(suggest_url)) = (&self.suggestions, &search.suggest) {
+        for suggestion in suggestions.get(suggest_url, typed) {
+          if suggestion != typed {
+            shortcuts.push(Self::shortcut(search, &suggestion, format!("search {engine} for {suggestion}")));
+          }
+        }
+      }
+    }
+    shortcuts
+  }
+  fn updated(&self) -> bool {
+    self.suggestions
+      .as_ref()
+      .is_some_and(|s| s.updated.swap(false, std::sync::atomic::Ordering::Relaxed))
+  }
+  fn pending(&self) -> bool {
+    self.suggestions.as_ref().is_some_and(|s| {
+      s.in_flight.load(std::sync::atomic::Ordering::Relaxed) > 0 ||
+        s.updated.load(std::sync::atomic::Ordering::Relaxed)
+    })
+  }
+}
+
+/// Searches with the URL of a bang, `!<name>` typed anywhere in the query, for the rest of it
+struct BangsProvider {
+  /// URL with `{query}` of every bang, by its name without the `!`
+  bangs: BTreeMap<String, String>,
+}
+
+impl Provider for BangsProvider {
+  fn name(&self) -> &'static str {
+    "bangs"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let Some((index, url)) = words
+      .iter()
+      .enumerate()
+      .find_map(|(i, word)| Some((i, self.bangs.get(word.strip_prefix('!')?)?))) else {
+      return vec![];
+    };
+    let rest = words
+      .iter()
+      .enumerate()
+      .filter(|(i, _)| *i != index)
+      .map(|(_, word)| *word)
+      .collect::<Vec<_>>()
+      .join(" ");
+    if rest.is_empty() {
+      return vec![];
+    }
+    vec![Shortcut {
+      path: fill_url(url, "query", &rest),
+      // the whole query, so `bullet '!yt lofi beats'` opens it right away
+      seq: vec![query.to_string()],
+      description: Some(format!("search {} for {rest}", words[index])),
+      kind: ShortcutKind::Url,
+      tags: vec!["search".to_string()],
+      ..Shortcut::default()
+    }]
+  }
+}
+
+/// Fills in the config's shortcuts with `{args}`, typed as `<seq> <args>`
+struct TemplatesProvider {
+  templates: Vec<Shortcut>,
+}
+
+impl Provider for TemplatesProvider {
+  fn name(&self) -> &'static str {
+    "templates"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    self.templates
+      .iter()
+      .filter_map(|template| Some(template.with_args(typed_after_seq(template, query)?)))
+      .collect()
+  }
+}
+
+/// Notes typed as `<seq> <text>` into the config's Note shortcuts, the text is kept in `args`
+struct NotesProvider {
+  notes: Vec<Shortcut>,
+}
+
+impl Provider for NotesProvider {
+  fn name(&self) -> &'static str {
+    "notes"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    self.notes
+      .iter()
+      .filter_map(|note| {
+        let typed = typed_after_seq(note, query)?;
+        Some(Shortcut {
+          seq: vec![format!("{} {typed}", note.name())],
+          args: vec![typed.to_string()],
+          ..note.clone()
+        })
+      })
+      .collect()
+  }
+}
+
+/// A result row printed by an external provider
+#[derive(Deserialize)]
+#[cfg(feature = "providers")]
+struct ExternalRow {
+  title: String,
+  subtitle: Option<String>,
+  action: ExternalAction,
+}
+
+/// What choosing an external row does
+#[derive(Deserialize)]
+#[serde(untagged)]
+#[cfg(feature = "providers")]
+enum ExternalAction {
+  /// A URL or path opened with the default handler
+  Open(String),
+  /// Opened like a config shortcut of that kind
+  Shortcut {
+    kind: ShortcutKind,
+    path: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    terminal: bool,
+  },
+}
+
+/// A provider written in any language: the command gets the query on stdin and prints
+/// a JSON array of `{"title", "subtitle", "action"}` rows, the action being a URL or path to open
+/// or `{"kind", "path", "args"}` like a shortcut of the config. It runs on every query, so it should answer quickly
+#[cfg(feature = "providers")]
+struct ExternalProvider {
+  config: ExternalProviderConfig,
+}
+
+#[cfg(feature = "providers")]
+impl ExternalProvider {
+  fn run(&self, query: &str) -> Result<Vec<ExternalRow>, String> {
+    let mut child = Command::new(&self.config.command)
+      .args(&self.config.args)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .map_err(|e| e.to_string())?;
+    child.stdin.take().unwrap().write_all(query.as_bytes()).map_err(|e| e.to_string())?;
+    // read meanwhile, a command printing more than the pipe holds would never exit otherwise
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = std::thread::spawn(move || {
+      let mut output = vec![];
+      std::io::Read::read_to_end(&mut stdout, &mut output).map(|_| output)
+    });
+    let deadline = std::time::Instant::now() + Duration::from_millis(self.config.timeout);
+    let status = loop {
+      match child.try_wait().map_err(|e| e.to_string())? {
+        Some(status) => break status,
+        None if std::time::Instant::now() >= deadline => {
+          // the answer is already given up on, a child that is gone meanwhile needs no killing
+          child.kill().ok();
+          child.wait().ok();
+          return Err(format!("killed after not answering within {}ms", self.config.timeout));
+        }
+        None => sleep(Duration::from_millis(5)),
+      }
+    };
+    let output = reader.join().unwrap().map_err(|e| e.to_string())?;
+    if !status.success() {
+      return Err(format!("exited with {status}"));
+    }
+    serde_json::from_slice(&output).map_err(|e| e.to_string())
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for ExternalProvider {
+  fn name(&self) -> &'static str {
+    "external"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let query = match &self.config.prefix {
+      Some(prefix) => match query.strip_prefix(prefix.as_str()) {
+        Some(rest) => rest,
+        None => return vec![],
+      },
+      None => query,
+    };
+    // every external provider is named `external`, the command tells them apart in the log
+    let rows = match self.run(query) {
+      Ok(rows) => rows,
+      Err(e) => {
+        error!(provider = self.name(), command = self.config.command, args = ?self.config.args, "external provider failed: {e}");
+        return vec![];
+      }
+    };
+    debug!(provider = self.name(), command = self.config.command, rows = rows.len(), "external provider answered");
+    rows
+      .into_iter()
+      .map(|row| {
+        let (kind, path, args, terminal) = match row.action {
+          ExternalAction::Open(target) => (ShortcutKind::Url, target, vec![], false),
+          ExternalAction::Shortcut { kind, path, args, terminal } => (kind, path, args, terminal),
+        };
+        Shortcut {
+          path,
+          args,
+          terminal,
+          seq: vec![row.title.to_lowercase()],
+          description: Some(row.subtitle.unwrap_or(row.title)),
+          kind,
+          ..Shortcut::default()
+        }
+      })
+      .collect()
+  }
+}
+
+/// Seqs for an entry known by its display name: the lowercased name, plus its initials
+/// when it has several words, so `Visual Studio Code` is also found with `vsc`
+pub(crate) fn seq_candidates(name: &str) -> Vec<String> {
+  let lower = name.to_lowercase();
+  let mut seqs = vec![lower.clone()];
+  let words: Vec<&str> = lower.split_whitespace().collect();
+  if words.len() > 1 {
+    seqs.push(words.iter().filter_map(|w| w.chars().next()).collect());
+  }
+  seqs
+}
+
+/// Collects files with one of `extensions` under `root`, descending at most `depth` levels
+#[cfg(feature = "providers")]
+fn collect_files(root: &Path, extensions: &[&str], depth: usize, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = fs::read_dir(root) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      if depth > 0 {
+        collect_files(&path, extensions, depth - 1, files);
+      }
+    } else if
+      path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+    {
+      files.push(path);
+    }
+  }
+}
+
+/// `.lnk` files of the user's and the shared Start Menu, opened through the shell
+#[cfg(feature = "providers")]
+struct StartMenuProvider;
+
+#[cfg(feature = "providers")]
+impl StartMenuProvider {
+  fn roots() -> Vec<PathBuf> {
+    let mut roots = vec![];
+    if let Some(base_dirs) = BaseDirs::new() {
+      roots.push(base_dirs.config_dir().join("Microsoft/Windows/Start Menu/Programs"));
+    }
+    if let Some(program_data) = std::env::var_os("ProgramData") {
+      roots.push(PathBuf::from(program_data).join("Microsoft/Windows/Start Menu/Programs"));
+    }
+    roots
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for StartMenuProvider {
+  fn name(&self) -> &'static str {
+    "start_menu"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut links = vec![];
+    for root in StartMenuProvider::roots() {
+      collect_files(&root, &["lnk"], 4, &mut links);
+    }
+    links
+      .into_iter()
+      .filter_map(|link| {
+        let name = link.file_stem()?.to_string_lossy().to_string();
+        Some(
+          Shortcut {
+            path: link.to_string_lossy().to_string(),
+            seq: seq_candidates(&name),
+            description: Some(name),
+            kind: ShortcutKind::App,
+            ..Shortcut::default()
+          }.with_os_path(&link)
+        )
+      })
+      .collect()
+  }
+}
+
+/// Splits a command line on whitespace, keeping double-quoted parts together
+#[cfg(feature = "providers")]
+fn split_command_line(line: &str) -> Vec<String> {
+  let mut args = vec![];
+  let mut current = String::new();
+  let mut in_arg = false;
+  let mut quoted = false;
+  let mut chars = line.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => {
+        quoted = !quoted;
+        in_arg = true;
+      }
+      '\\' if quoted => {
+        if let Some(escaped) = chars.next() {
+          current.push(escaped);
+        }
+      }
+      c if c.is_whitespace() && !quoted => {
+        if in_arg {
+          args.push(std::mem::take(&mut current));
+          in_arg = false;
+        }
+      }
+      c => {
+        current.push(c);
+        in_arg = true;
+      }
+    }
+  }
+  if in_arg {
+    args.push(current);
+  }
+  args
+}
+
+/// Applications listed by `.desktop` files in the XDG data directories
+#[cfg(feature = "providers")]
+struct DesktopEntriesProvider;
+
+#[cfg(feature = "providers")]
+impl DesktopEntriesProvider {
+  fn roots() -> Vec<PathBuf> {
+    let data_home = std::env
+      ::var_os("XDG_DATA_HOME")
+      .map(PathBuf::from)
+      .or_else(|| BaseDirs::new().map(|dirs| dirs.data_dir().to_path_buf()));
+    let data_dirs = std::env
+      ::var("XDG_DATA_DIRS")
+      .ok()
+      .filter(|dirs| !dirs.is_empty())
+      .unwrap_or("/usr/local/share:/usr/share".to_string());
+    data_home
+      .into_iter()
+      .chain(data_dirs.split(':').map(PathBuf::from))
+      .map(|dir| dir.join("applications"))
+      .collect()
+  }
+  /// Reads the `[Desktop Entry]` group, skipping hidden entries and ones that are not applications
+  fn parse(content: &str) -> Option<Shortcut> {
+    let mut in_entry = false;
+    let mut name = None;
+    let mut comment = None;
+    let mut exec = None;
+    let mut terminal = false;
+    for line in content.lines().map(str::trim) {
+      if line.starts_with('[') {
+        in_entry = line == "[Desktop Entry]";
+        continue;
+      }
+      if !in_entry {
+        continue;
+      }
+      let Some((key, value)) = line.split_once('=') else {
+        continue;
+      };
+      match (key.trim(), value.trim()) {
+        ("Type", kind) if kind != "Application" => {
+          return None;
+        }
+        ("NoDisplay" | "Hidden", "true") => {
+          return None;
+        }
+        ("Name", value) => {
+          name = Some(value.to_string());
+        }
+        ("Comment", value) => {
+          comment = Some(value.to_string());
+        }
+        ("Exec", value) => {
+          exec = Some(value.to_string());
+        }
+        ("Terminal", value) => {
+          terminal = value == "true";
+        }
+        _ => {}
+      }
+    }
+    // field codes like %f or %U stand for files passed by a file manager, bullet passes none
+    let mut command: Vec<String> = split_command_line(&exec?)
+      .into_iter()
+      .filter(|arg| !(arg.len() == 2 && arg.starts_with('%') && arg != "%%"))
+      .map(|arg| arg.replace("%%", "%"))
+      .collect();
+    if command.is_empty() {
+      return None;
+    }
+    let name = name?;
+    Some(Shortcut {
+      path: command.remove(0),
+      seq: seq_candidates(&name),
+      description: Some(comment.map(|c| format!("{name} - {c}")).unwrap_or(name)),
+      kind: ShortcutKind::App,
+      args: command,
+      terminal,
+      ..Shortcut::default()
+    })
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for DesktopEntriesProvider {
+  fn name(&self) -> &'static str {
+    "desktop_entries"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    // an entry in the user's data dir overrides the system one with the same file name
+    let mut seen_ids = std::collections::HashSet::new();
+    for root in DesktopEntriesProvider::roots() {
+      let mut files = vec![];
+      collect_files(&root, &["desktop"], 2, &mut files);
+      for file in files {
+        let id = file.strip_prefix(&root).unwrap_or(&file).to_path_buf();
+        if !seen_ids.insert(id) {
+          continue;
+        }
+        if let Some(shortcut) = fs::read_to_string(&file).ok().and_then(|c| Self::parse(&c)) {
+          shortcuts.push(shortcut);
+        }
+      }
+    }
+    shortcuts
+  }
+}
+
+/// `.app` bundles of the Applications folders, launched with `open -a`
+#[cfg(feature = "providers")]
+struct MacApplicationsProvider;
+
+#[cfg(feature = "providers")]
+impl MacApplicationsProvider {
+  /// Bundles are directories too, so they are collected without descending into them
+  fn collect_bundles(dir: &Path, depth: usize, bundles: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+      return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+      if path.extension().is_some_and(|e| e == "app") {
+        bundles.push(path);
+      } else if path.is_dir() && depth > 0 {
+        Self::collect_bundles(&path, depth - 1, bundles);
+      }
+    }
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for MacApplicationsProvider {
+  fn name(&self) -> &'static str {
+    "applications"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut roots = vec![PathBuf::from("/Applications")];
+    if let Some(base_dirs) = BaseDirs::new() {
+      roots.push(base_dirs.home_dir().join("Applications"));
+    }
+    let mut bundles = vec![];
+    for root in roots {
+      Self::collect_bundles(&root, 1, &mut bundles);
+    }
+    bundles
+      .into_iter()
+      .filter_map(|bundle| {
+        let name = bundle.file_stem()?.to_str()?.to_string();
+        Some(Shortcut {
+          path: "open".to_string(),
+          args: vec!["-a".to_string(), bundle.to_str()?.to_string()],
+          seq: seq_candidates(&name),
+          description: Some(name),
+          kind: ShortcutKind::App,
+          ..Shortcut::default()
+        })
+      })
+      .collect()
+  }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[cfg(feature = "providers")]
+struct PathExecutablesCache {
+  /// Value of `PATH` the cache was built from
+  path: String,
+  /// Modification times of the `PATH` directories, in seconds since the epoch
+  modified: Vec<Option<u64>>,
+  executables: Vec<PathBuf>,
+}
+
+/// Executables on `PATH`, started in a terminal. The scan is cached in the data dir
+/// until `PATH` or one of its directories changes
+#[cfg(feature = "providers")]
+struct PathExecutablesProvider;
+
+#[cfg(feature = "providers")]
+impl PathExecutablesProvider {
+  fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+      let extensions = std::env::var("PATHEXT").unwrap_or(".EXE;.CMD;.BAT;.COM".to_string());
+      path.is_file() &&
+        path
+          .extension()
+          .and_then(|e| e.to_str())
+          .is_some_and(|e| extensions.split(';').any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(e)))
+    }
+  }
+  fn scan(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut names = std::collections::HashSet::new();
+    let mut executables = vec![];
+    for dir in dirs {
+      let Ok(entries) = fs::read_dir(dir) else {
+        continue;
+      };
+      let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+      paths.sort();
+      for path in paths {
+        // like the shell, the first directory on PATH wins
+        if Self::is_executable(&path) && names.insert(path.file_stem().map(|n| n.to_os_string())) {
+          executables.push(path);
+        }
+      }
+    }
+    executables
+  }
+  fn executables() -> Vec<PathBuf> {
+    let path = std::env::var("PATH").unwrap_or_default();
+    let dirs: Vec<PathBuf> = std::env::split_paths(&path).collect();
+    let modified: Vec<Option<u64>> = dirs
+      .iter()
+      .map(|dir| {
+        let modified = dir.metadata().and_then(|m| m.modified()).ok()?;
+        modified.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs())
+      })
+      .collect();
+    let cache_path = data_dir().map(|dir| dir.join("path_executables.json"));
+    let cached = cache_path
+      .as_ref()
+      .and_then(|p| fs::read_to_string(p).ok())
+      .and_then(|c| serde_json::from_str::<PathExecutablesCache>(&c).ok());
+    if let Some(cache) = cached {
+      if cache.path == path && cache.modified == modified {
+        return cache.executables;
+      }
+    }
+    let executables = Self::scan(&dirs);
+    debug!(executables = executables.len(), "scanned PATH");
+    if let Some(cache_path) = cache_path {
+      let cache = PathExecutablesCache { path, modified, executables };
+      if let Ok(content) = serde_json::to_string(&cache) {
+        write_cache(&cache_path, &content);
+      }
+      return cache.executables;
+    }
+    executables
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for PathExecutablesProvider {
+  fn name(&self) -> &'static str {
+    "path_executables"
+  }
+  fn prefix(&self) -> Option<&'static str> {
+    Some("$")
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    Self::executables()
+      .into_iter()
+      .filter_map(|executable| {
+        let name = executable.file_stem()?.to_string_lossy().to_string();
+        Some(
+          Shortcut {
+            path: executable.to_string_lossy().to_string(),
+            seq: vec![name],
+            kind: ShortcutKind::App,
+            terminal: true,
+            ..Shortcut::default()
+          }.with_os_path(&executable)
+        )
+      })
+      .collect()
+  }
+}
+
+/// Bookmarks of Chromium based browsers and Firefox, as URL shortcuts tagged with their folders
+#[cfg(feature = "providers")]
+struct BookmarksProvider;
+
+#[cfg(feature = "providers")]
+impl BookmarksProvider {
+  /// Directories holding one folder per Chrome or Edge profile
+  fn chromium_roots() -> Vec<PathBuf> {
+    let Some(base_dirs) = BaseDirs::new() else {
+      return vec![];
+    };
+    if cfg!(windows) {
+      let local = base_dirs.data_local_dir();
+      vec![local.join("Google/Chrome/User Data"), local.join("Microsoft/Edge/User Data")]
+    } else {
+      // ~/Library/Application Support on macOS, ~/.config elsewhere
+      let config = base_dirs.config_dir();
+      if cfg!(target_os = "macos") {
+        vec![config.join("Google/Chrome"), config.join("Microsoft Edge"), config.join("Chromium")]
+      } else {
+        vec![config.join("google-chrome"), config.join("microsoft-edge"), config.join("chromium")]
+      }
+    }
+  }
+  fn firefox_root() -> Option<PathBuf> {
+    let base_dirs = BaseDirs::new()?;
+    Some(
+      if cfg!(windows) || cfg!(target_os = "macos") {
+        base_dirs.config_dir().join(if cfg!(windows) { "Mozilla/Firefox/Profiles" } else { "Firefox/Profiles" })
+      } else {
+        base_dirs.home_dir().join(".mozilla/firefox")
+      }
+    )
+  }
+  fn profile_files(root: &Path, file_name: &str) -> Vec<PathBuf> {
+    fs::read_dir(root)
+      .map(|entries| {
+        entries
+          .flatten()
+          .map(|e| e.path().join(file_name))
+          .filter(|p| p.is_file())
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+  fn bookmark(title: &str, url: &str, folders: Vec<String>) -> Shortcut {
+    let title = if title.trim().is_empty() { url } else { title };
+    Shortcut {
+      path: url.to_string(),
+      seq: seq_candidates(title),
+      description: Some(title.to_string()),
+      kind: ShortcutKind::Url,
+      tags: folders.iter().map(|f| f.to_lowercase()).collect(),
+      ..Shortcut::default()
+    }
+  }
+  /// Walks a node of a Chromium `Bookmarks` file, `folders` being the folders above it
+  fn collect_chromium(node: &serde_json::Value, folders: &mut Vec<String>, shortcuts: &mut Vec<Shortcut>) {
+    let name = node["name"].as_str().unwrap_or_default();
+    match node["type"].as_str() {
+      Some("url") => {
+        if let Some(url) = node["url"].as_str() {
+          shortcuts.push(Self::bookmark(name, url, folders.clone()));
+        }
+      }
+      Some("folder") => {
+        folders.push(name.to_string());
+        for child in node["children"].as_array().into_iter().flatten() {
+          Self::collect_chromium(child, folders, shortcuts);
+        }
+        folders.pop();
+      }
+      _ => {}
+    }
+  }
+  fn chromium_bookmarks(file: &Path) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    let content = fs::read_to_string(file).unwrap_or_default();
+    let Ok(bookmarks) = serde_json::from_str::<serde_json::Value>(&content) else {
+      return shortcuts;
+    };
+    for root in bookmarks["roots"].as_object().into_iter().flat_map(|roots| roots.values()) {
+      // the roots are "Bookmarks bar" and the like, only folders below them are useful tags
+      for child in root["children"].as_array().into_iter().flatten() {
+        Self::collect_chromium(child, &mut vec![], &mut shortcuts);
+      }
+    }
+    shortcuts
+  }
+  fn firefox_bookmarks(places: &Path) -> rusqlite::Result<Vec<Shortcut>> {
+    // immutable skips locking, Firefox keeps the database locked while running
+    let uri = format!("file:{}?immutable=1", places.to_str().unwrap_or_default());
+    let db = rusqlite::Connection::open_with_flags(
+      uri,
+      rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI
+    )?;
+    let mut folders = std::collections::HashMap::new();
+    let mut statement = db.prepare("SELECT id, parent, title FROM moz_bookmarks WHERE type = 2")?;
+    let rows = statement.query_map([], |row| {
+      Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, Option<String>>(2)?))
+    })?;
+    for (id, parent, title) in rows.flatten() {
+      folders.insert(id, (parent, title.unwrap_or_default()));
+    }
+    let mut statement = db.prepare(
+      "SELECT b.parent, b.title, p.url FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk WHERE b.type = 1 AND p.url LIKE 'http%'"
+    )?;
+    let rows = statement.query_map([], |row| {
+      Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, String>(2)?))
+    })?;
+    let mut shortcuts = vec![];
+    for (parent, title, url) in rows.flatten() {
+      let mut chain = vec![];
+      let mut folder = parent;
+      while let Some((grand_parent, folder_title)) = folders.get(&folder) {
+        chain.push(folder_title.clone());
+        folder = *grand_parent;
+      }
+      // the last two are the nameless root and "menu", "toolbar" or "unfiled"
+      chain.truncate(chain.len().saturating_sub(2));
+      chain.reverse();
+      shortcuts.push(Self::bookmark(&title.unwrap_or_default(), &url, chain));
+    }
+    Ok(shortcuts)
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for BookmarksProvider {
+  fn name(&self) -> &'static str {
+    "bookmarks"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    for root in Self::chromium_roots() {
+      for file in Self::profile_files(&root, "Bookmarks") {
+        shortcuts.extend(Self::chromium_bookmarks(&file));
+      }
+    }
+    for places in Self::firefox_root().map(|root| Self::profile_files(&root, "places.sqlite")).unwrap_or_default() {
+      match Self::firefox_bookmarks(&places) {
+        Ok(bookmarks) => shortcuts.extend(bookmarks),
+        Err(e) => error!(path = ?places, "failed to read firefox bookmarks: {e}"),
+      }
+    }
+    shortcuts
+  }
+}
+
+/// Git repositories under the configured roots, as Dir shortcuts named after the repository.
+/// The worker caches the last scan, so results show up instantly while a new scan runs
+#[cfg(feature = "providers")]
+struct GitReposProvider {
+  config: GitReposConfig,
+}
+
+#[cfg(feature = "providers")]
+impl GitReposProvider {
+  fn scan(dir: &Path, depth: usize, repos: &mut Vec<PathBuf>) {
+    if dir.join(".git").exists() {
+      repos.push(dir.to_path_buf());
+      return;
+    }
+    if depth == 0 {
+      return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+      return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+      let skipped = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_none_or(|n| n.starts_with('.') || n == "node_modules" || n == "target");
+      if path.is_dir() && !skipped {
+        Self::scan(&path, depth - 1, repos);
+      }
+    }
+  }
+  fn scan_roots(config: &GitReposConfig) -> Vec<PathBuf> {
+    let mut repos = vec![];
+    for root in &config.roots {
+      Self::scan(Path::new(root), config.depth, &mut repos);
+    }
+    repos.sort();
+    repos
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for GitReposProvider {
+  fn name(&self) -> &'static str {
+    "git_repos"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    for repo in Self::scan_roots(&self.config) {
+      let Some(name) = repo.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        continue;
+      };
+      shortcuts.push(
+        Shortcut {
+          path: repo.to_string_lossy().to_string(),
+          seq: vec![name.to_lowercase()],
+          description: Some(format!("{name} repository")),
+          kind: ShortcutKind::Dir,
+          tags: vec!["git".to_string()],
+          ..Shortcut::default()
+        }.with_os_path(&repo)
+      );
+      // the editor is given the path as text, so a repo without a UTF-8 path is only opened as a folder
+      if let (Some(editor), Some(path)) = (&self.config.editor, repo.to_str()) {
+        shortcuts.push(Shortcut {
+          path: editor.clone(),
+          args: vec![path.to_string()],
+          seq: vec![format!("edit {}", name.to_lowercase())],
+          description: Some(format!("open {name} in {editor}")),
+          kind: ShortcutKind::App,
+          ..Shortcut::default()
+        });
+      }
+    }
+    shortcuts
+  }
+}
+
+/// Folders and workspaces recently opened in VS Code, as App shortcuts opening them in the editor
+#[cfg(feature = "providers")]
+struct VsCodeRecentProvider;
+
+#[cfg(feature = "providers")]
+impl VsCodeRecentProvider {
+  /// Config directory of each VS Code flavour with the command that opens it
+  fn installs() -> Vec<(PathBuf, &'static str)> {
+    let Some(base_dirs) = BaseDirs::new() else {
+      return vec![];
+    };
+    // %APPDATA% on Windows, ~/Library/Application Support on macOS, ~/.config elsewhere
+    let config = base_dirs.config_dir();
+    vec![
+      (config.join("Code"), "code"),
+      (config.join("Code - Insiders"), "code-insiders"),
+      (config.join("VSCodium"), "codium")
+    ]
+  }
+  /// Entries of `history.recentlyOpenedPathsList`, which VS Code keeps in its global state database
+  fn recent_entries(state: &Path) -> rusqlite::Result<Vec<serde_json::Value>> {
+    // immutable skips locking, VS Code keeps the database open while running
+    let uri = format!("file:{}?immutable=1", state.to_str().unwrap_or_default());
+    let db = rusqlite::Connection::open_with_flags(
+      uri,
+      rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI
+    )?;
+    let value: String = db.query_row(
+      "SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'",
+      [],
+      |row| row.get(0)
+    )?;
+    let list = serde_json::from_str::<serde_json::Value>(&value).unwrap_or_default();
+    Ok(list["entries"].as_array().cloned().unwrap_or_default())
+  }
+  /// Local path of a `file://` URI, `None` for remote ones
+  fn uri_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = vec![];
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+      let escaped = tail
+        .get(..2)
+        .and_then(|hex| std::str::from_utf8(hex).ok())
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+      match escaped {
+        Some(escaped) if byte == b'%' => {
+          bytes.push(escaped);
+          rest = &tail[2..];
+        }
+        _ => {
+          bytes.push(byte);
+          rest = tail;
+        }
+      }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // file:///c:/Users on Windows
+    Some(if cfg!(windows) { path.trim_start_matches('/').to_string() } else { path })
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for VsCodeRecentProvider {
+  fn name(&self) -> &'static str {
+    "vscode_recent"
+  }
+  fn cached(&self) -> bool {
+    true
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    for (dir, command) in Self::installs() {
+      let state = dir.join("User/globalStorage/state.vscdb");
+      if !state.is_file() {
+        continue;
+      }
+      let entries = match Self::recent_entries(&state) {
+        Ok(entries) => entries,
+        Err(e) => {
+          error!(path = ?state, "failed to read recently opened vscode folders: {e}");
+          continue;
+        }
+      };
+      for entry in entries {
+        let uri = entry["folderUri"].as_str().or(entry["workspace"]["configPath"].as_str());
+        let Some(path) = uri.and_then(Self::uri_path) else {
+          continue;
+        };
+        let Some(name) = Path::new(&path).file_stem().and_then(|n| n.to_str()).map(str::to_string) else {
+          continue;
+        };
+        shortcuts.push(Shortcut {
+          path: command.to_string(),
+          args: vec![path.clone()],
+          seq: seq_candidates(&name),
+          description: Some(path),
+          kind: ShortcutKind::App,
+          tags: vec![command.to_string()],
+          ..Shortcut::default()
+        });
+      }
+    }
+    shortcuts
+  }
+}
+
+/// Hosts from the user's ssh config and known hosts, as Ssh shortcuts
+#[cfg(feature = "providers")]
+struct SshHostsProvider;
+
+#[cfg(feature = "providers")]
+impl SshHostsProvider {
+  /// `Host` aliases without patterns, with the `User@HostName` they resolve to
+  fn config_hosts(content: &str) -> Vec<(String, Option<String>)> {
+    // every block is its aliases, user and host name
+    let mut blocks: Vec<(Vec<String>, Option<String>, Option<String>)> = vec![];
+    for line in content.lines().map(str::trim) {
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+        Some((k, v)) => (k.to_lowercase(), v.trim_matches(|c: char| c.is_whitespace() || c == '=')),
+        None => (line.to_lowercase(), ""),
+      };
+      match keyword.as_str() {
+        "host" => {
+          let aliases = value
+            .split_whitespace()
+            .filter(|alias| !alias.contains(['*', '?', '!']))
+            .map(|alias| alias.to_string())
+            .collect();
+          blocks.push((aliases, None, None));
+        }
+        "match" => blocks.push((vec![], None, None)),
+        "user" => if let Some(block) = blocks.last_mut() {
+          block.1 = Some(value.to_string());
+        }
+        "hostname" => if let Some(block) = blocks.last_mut() {
+          block.2 = Some(value.to_string());
+        }
+        _ => {}
+      }
+    }
+    blocks
+      .into_iter()
+      .flat_map(|(aliases, user, host_name)| {
+        aliases.into_iter().map(move |alias| {
+          // without a HostName the alias is the host
+          let target = match (&user, &host_name) {
+            (Some(user), Some(host_name)) => Some(format!("{user}@{host_name}")),
+            (None, host_name) => host_name.clone(),
+            (Some(user), None) => Some(format!("{user}@{alias}")),
+          };
+          (alias, target)
+        })
+      })
+      .collect()
+  }
+  /// Host names of `known_hosts`, skipping hashed entries and bare ip addresses
+  fn known_hosts(content: &str) -> Vec<String> {
+    let mut hosts = vec![];
+    for line in content.lines().map(str::trim) {
+      let Some(names) = line.split_whitespace().next() else {
+        continue;
+      };
+      if names.starts_with(['#', '|', '@']) {
+        continue;
+      }
+      for name in names.split(',') {
+        let host = match name.strip_prefix('[').and_then(|n| n.split_once("]:")) {
+          Some((host, port)) => (host, Some(port)),
+          None => (name, None),
+        };
+        if host.0.parse::<std::net::IpAddr>().is_ok() {
+          continue;
+        }
+        hosts.push(match host {
+          (host, Some(port)) => format!("ssh://{host}:{port}"),
+          (host, None) => host.to_string(),
+        });
+      }
+    }
+    hosts
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for SshHostsProvider {
+  fn name(&self) -> &'static str {
+    "ssh_hosts"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let Some(ssh_dir) = BaseDirs::new().map(|dirs| dirs.home_dir().join(".ssh")) else {
+      return vec![];
+    };
+    let config = fs::read_to_string(ssh_dir.join("config")).unwrap_or_default();
+    let known_hosts = fs::read_to_string(ssh_dir.join("known_hosts")).unwrap_or_default();
+    let mut seen = std::collections::HashSet::new();
+    let mut shortcuts = vec![];
+    let hosts = Self::config_hosts(&config)
+      .into_iter()
+      .chain(Self::known_hosts(&known_hosts).into_iter().map(|host| (host, None)));
+    for (host, target) in hosts {
+      if !seen.insert(host.clone()) {
+        continue;
+      }
+      shortcuts.push(Shortcut {
+        seq: vec![host.trim_start_matches("ssh://").to_lowercase()],
+        description: target,
+        path: host,
+        kind: ShortcutKind::Ssh,
+        ..Shortcut::default()
+      });
+    }
+    shortcuts
+  }
+}
+
+/// Attaches to running tmux sessions, or switches to them when bullet itself runs inside tmux
+#[cfg(feature = "providers")]
+struct TmuxProvider;
+
+#[cfg(feature = "providers")]
+impl TmuxProvider {
+  fn inside_tmux() -> bool {
+    std::env::var_os("TMUX").is_some()
+  }
+  /// Inside tmux the client switches over, outside a terminal is opened to run `command`
+  fn shortcut(seq: String, description: String, command: Vec<&str>, switch: Vec<&str>) -> Shortcut {
+    let (kind, args) = if Self::inside_tmux() {
+      (ShortcutKind::App, switch)
+    } else {
+      (ShortcutKind::Terminal, command)
+    };
+    Shortcut {
+      path: "tmux".to_string(),
+      args: args.into_iter().map(String::from).collect(),
+      seq: vec![seq],
+      description: Some(description),
+      kind,
+      tags: vec!["tmux".to_string()],
+      ..Shortcut::default()
+    }
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for TmuxProvider {
+  fn name(&self) -> &'static str {
+    "tmux"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    let output = Command::new("tmux")
+      .args(["list-sessions", "-F", "#{session_name}\t#{session_windows}\t#{session_attached}"])
+      .output();
+    let Ok(output) = output else {
+      return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| {
+        let mut fields = line.split('\t');
+        let (name, windows, attached) = (fields.next()?, fields.next()?, fields.next()?);
+        let attached = if attached == "0" { "" } else { ", attached" };
+        Some(
+          Self::shortcut(
+            name.to_string(),
+            format!("tmux session, {windows} windows{attached}"),
+            vec!["attach-session", "-t", name],
+            vec!["switch-client", "-t", name]
+          )
+        )
+      })
+      .collect()
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let Some(name) = query.strip_prefix("tmux ").map(str::trim).filter(|n| !n.is_empty()) else {
+      return vec![];
+    };
+    vec![
+      Self::shortcut(
+        query.to_string(),
+        format!("new tmux session {name}"),
+        vec!["new-session", "-s", name],
+        vec!["new-session", "-d", "-s", name, ";", "switch-client", "-t", name]
+      )
+    ]
+  }
+}
+
+/// Locking the session and power actions. The ones closing programs always ask for confirmation
+#[cfg(feature = "providers")]
+struct SystemActionsProvider;
+
+#[cfg(feature = "providers")]
+impl SystemActionsProvider {
+  /// Name, whether it closes programs, and the command of each action the platform has
+  fn actions() -> Vec<(&'static str, bool, Vec<String>)> {
+    let actions: Vec<(&str, bool, Vec<&str>)> = if cfg!(windows) {
+      vec![
+        ("lock", false, vec!["rundll32.exe", "user32.dll,LockWorkStation"]),
+        ("sleep", false, vec!["rundll32.exe", "powrprof.dll,SetSuspendState", "0,1,0"]),
+        ("hibernate", false, vec!["shutdown", "/h"]),
+        ("restart", true, vec!["shutdown", "/r", "/t", "0"]),
+        ("shut down", true, vec!["shutdown", "/s", "/t", "0"]),
+        ("log out", true, vec!["shutdown", "/l"])
+      ]
+    } else if cfg!(target_os = "macos") {
+      vec![
+        ("lock", false, vec!["pmset", "displaysleepnow"]),
+        ("sleep", false, vec!["pmset", "sleepnow"]),
+        ("restart", true, vec!["osascript", "-e", "tell application \"System Events\" to restart"]),
+        ("shut down", true, vec!["osascript", "-e", "tell application \"System Events\" to shut down"]),
+        ("log out", true, vec!["osascript", "-e", "tell application \"System Events\" to log out"])
+      ]
+    } else {
+      vec![
+        ("lock", false, vec!["loginctl", "lock-session"]),
+        ("sleep", false, vec!["systemctl", "suspend"]),
+        ("hibernate", false, vec!["systemctl", "hibernate"]),
+        ("restart", true, vec!["systemctl", "reboot"]),
+        ("shut down", true, vec!["systemctl", "poweroff"]),
+        ("log out", true, vec!["loginctl", "terminate-session", "self"])
+      ]
+    };
+    actions
+      .into_iter()
+      .map(|(name, closes, command)| (name, closes, command.into_iter().map(String::from).collect()))
+      .collect()
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for SystemActionsProvider {
+  fn name(&self) -> &'static str {
+    "system_actions"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    Self::actions()
+      .into_iter()
+      .map(|(name, closes, mut command)| Shortcut {
+        path: command.remove(0),
+        args: command,
+        seq: seq_candidates(name),
+        description: Some(format!("{name} the computer")),
+        kind: ShortcutKind::App,
+        tags: vec!["system".to_string()],
+        confirm: closes,
+        ..Shortcut::default()
+      })
+      .collect()
+  }
+}
+
+/// Starts, stops and restarts systemd services found with `svc <name>`.
+/// System units are managed with `sudo` in a terminal, after confirming
+#[cfg(feature = "providers")]
+struct ServicesProvider;
+
+#[cfg(feature = "providers")]
+impl ServicesProvider {
+  /// Names and active states of the services of the user or the system manager
+  fn list(user: bool) -> Vec<(String, String)> {
+    let mut command = Command::new("systemctl");
+    if user {
+      command.arg("--user");
+    }
+    let Ok(output) = command.args(["list-units", "--type=service", "--all", "--no-legend", "--plain"]).output() else {
+      return vec![];
+    };
+    // nginx.service loaded active running A high performance web server
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| {
+        let mut fields = line.split_whitespace();
+        let unit = fields.next()?;
+        let active = fields.nth(1)?;
+        Some((unit.to_string(), active.to_string()))
+      })
+      .collect()
+  }
+  fn shortcut(unit: &str, active: &str, action: &str, user: bool) -> Shortcut {
+    let name = unit.trim_end_matches(".service");
+    let (path, args, kind) = if user {
+      ("systemctl", vec!["--user", action, unit], ShortcutKind::App)
+    } else {
+      ("sudo", vec!["systemctl", action, unit], ShortcutKind::Terminal)
+    };
+    Shortcut {
+      path: path.to_string(),
+      args: args.into_iter().map(String::from).collect(),
+      seq: vec![format!("{action} {}", name.to_lowercase())],
+      description: Some(format!("{action} {unit} ({}, {active})", if user { "user" } else { "system" })),
+      kind,
+      tags: vec!["svc".to_string()],
+      confirm: !user,
+      ..Shortcut::default()
+    }
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for ServicesProvider {
+  fn name(&self) -> &'static str {
+    "services"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let Some(name) = query.strip_prefix("svc ").map(|n| n.trim().to_lowercase()) else {
+      return vec![];
+    };
+    let mut shortcuts = vec![];
+    for user in [true, false] {
+      for (unit, active) in Self::list(user) {
+        if !unit.to_lowercase().contains(&name) {
+          continue;
+        }
+        let actions: &[&str] = if active == "active" { &["restart", "stop"] } else { &["start"] };
+        for action in actions {
+          shortcuts.push(Self::shortcut(&unit, &active, action, user));
+        }
+      }
+    }
+    shortcuts
+  }
+}
+
+/// Starts and stops docker containers and compose projects found with `docker <name>`,
+/// opens a shell in running containers and their published ports in the browser
+#[cfg(feature = "docker")]
+struct DockerProvider;
+
+#[cfg(feature = "docker")]
+impl DockerProvider {
+  /// Lines of the `docker` command's output, parsed as JSON
+  fn json_lines(args: &[&str]) -> Vec<serde_json::Value> {
+    let Ok(output) = Command::new("docker").args(args).output() else {
+      return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| serde_json::from_str(line).ok())
+      .collect()
+  }
+  fn action(seq: String, description: String, args: Vec<String>) -> Shortcut {
+    Shortcut {
+      path: "docker".to_string(),
+      args,
+      seq: vec![seq],
+      description: Some(description),
+      kind: ShortcutKind::App,
+      tags: vec!["docker".to_string()],
+      ..Shortcut::default()
+    }
+  }
+  /// Host ports of `0.0.0.0:8080->80/tcp, :::8080->80/tcp`
+  fn published_ports(ports: &str) -> Vec<String> {
+    let mut published: Vec<String> = ports
+      .split(", ")
+      .filter_map(|mapping| mapping.split_once("->")?.0.rsplit_once(':').map(|(_, port)| port.to_string()))
+      .collect();
+    published.dedup();
+    published
+  }
+  fn containers(name: &str) -> Vec<Shortcut> {
+    let mut shortcuts = vec![];
+    for container in Self::json_lines(&["ps", "--all", "--format", "{{json .}}"]) {
+      let (Some(names), Some(state)) = (container["Names"].as_str(), container["State"].as_str()) else {
+        continue;
+      };
+      let container_name = names.split(',').next().unwrap_or(names);
+      if !container_name.to_lowercase().contains(name) {
+        continue;
+      }
+      let seq = container_name.to_lowercase();
+      let image = container["Image"].as_str().unwrap_or_default();
+      if state != "running" {
+        shortcuts.push(
+          Self::action(
+            format!("start {seq}"),
+            format!("start container {container_name} ({image}, {state})"),
+            vec!["start".to_string(), container_name.to_string()]
+          )
+        );
+        continue;
+      }
+      shortcuts.push(
+        Self::action(
+          format!("stop {seq}"),
+          format!("stop container {container_name} ({image})"),
+          vec!["stop".to_string(), container_name.to_string()]
+        )
+      );
+      shortcuts.push(Shortcut {
+        kind: ShortcutKind::Terminal,
+        ..Self::action(
+          format!("shell {seq}"),
+          format!("shell in container {container_name}"),
+          vec!["exec".to_string(), "-it".to_string(), container_name.to_string(), "sh".to_string()]
+        )
+      });
+      for port in Self::published_ports(container["Ports"].as_str().unwrap_or_default()) {
+        shortcuts.push(Shortcut {
+          path: format!("http://localhost:{port}"),
+          seq: vec![format!("open {seq}")],
+          description: Some(format!("{container_name} on port {port}")),
+          kind: ShortcutKind::Url,
+          tags: vec!["docker".to_string()],
+          ..Shortcut::default()
+        });
+      }
+    }
+    shortcuts
+  }
+  fn compose_projects(name: &str) -> Vec<Shortcut> {
+    let Some(projects) = Self::json_lines(&["compose", "ls", "--all", "--format", "json"]).pop() else {
+      return vec![];
+    };
+    let mut shortcuts = vec![];
+    // [{"Name":"homelab","Status":"running(3)","ConfigFiles":"/srv/homelab/compose.yaml"}]
+    for project in projects.as_array().into_iter().flatten() {
+      let (Some(project_name), Some(status)) = (project["Name"].as_str(), project["Status"].as_str()) else {
+        continue;
+      };
+      if !project_name.to_lowercase().contains(name) {
+        continue;
+      }
+      let action = if status.starts_with("running") { "stop" } else { "start" };
+      shortcuts.push(
+        Self::action(
+          format!("{action} {}", project_name.to_lowercase()),
+          format!("{action} compose project {project_name} ({status})"),
+          vec!["compose", "--project-name", project_name, action].into_iter().map(String::from).collect()
+        )
+      );
+    }
+    shortcuts
+  }
+}
+
+#[cfg(feature = "docker")]
+impl Provider for DockerProvider {
+  fn name(&self) -> &'static str {
+    "docker"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let Some(name) = query.strip_prefix("docker ").map(|n| n.trim().to_lowercase()) else {
+      return vec![];
+    };
+    let mut shortcuts = Self::containers(&name);
+    shortcuts.extend(Self::compose_projects(&name));
+    shortcuts
+  }
+}
+
+/// Entries of a password manager. Choosing one copies its password or one-time code,
+/// which is fetched only then and never kept in the shortcut, so it stays out of logs and history
+#[cfg(feature = "providers")]
+struct PasswordsProvider {
+  config: PasswordsConfig,
+  /// Ids and names of the entries, listed on the first search since the CLIs are slow
+  entries: std::sync::OnceLock<Vec<(String, String)>>,
+}
+
+#[cfg(feature = "providers")]
+impl PasswordsProvider {
+  fn list(&self) -> Vec<(String, String)> {
+    match self.config.manager {
+      PasswordManager::Pass => {
+        let store = std::env
+          ::var_os("PASSWORD_STORE_DIR")
+          .map(PathBuf::from)
+          .or_else(|| BaseDirs::new().map(|dirs| dirs.home_dir().join(".password-store")));
+        let mut files = vec![];
+        if let Some(store) = &store {
+          collect_files(store, &["gpg"], 8, &mut files);
+        }
+        files
+          .iter()
+          .filter_map(|file| {
+            let entry = file.strip_prefix(store.as_ref()?).ok()?.with_extension("");
+            let entry = entry.to_str()?.replace('\\', "/");
+            Some((entry.clone(), entry))
+          })
+          .collect()
+      }
+      PasswordManager::Bitwarden | PasswordManager::OnePassword => {
+        let (program, args, name_key) = if self.config.manager == PasswordManager::Bitwarden {
+          ("bw", vec!["list", "items"], "name")
+        } else {
+          ("op", vec!["item", "list", "--format", "json"], "title")
+        };
+        let output = Command::new(program).args(args).stderr(Stdio::null()).output();
+        let items = output
+          .ok()
+          .and_then(|o| serde_json::from_slice::<serde_json::Value>(&o.stdout).ok())
+          .unwrap_or_default();
+        items
+          .as_array()
+          .into_iter()
+          .flatten()
+          .filter_map(|item| Some((item["id"].as_str()?.to_string(), item[name_key].as_str()?.to_string())))
+          .collect()
+      }
+    }
+  }
+  /// Command printing the password, or the one-time code when `otp` is set
+  fn secret_command(&self, id: &str, otp: bool) -> (&'static str, Vec<String>) {
+    let (program, args) = match (self.config.manager, otp) {
+      (PasswordManager::Pass, false) => ("pass", vec!["show", id]),
+      (PasswordManager::Pass, true) => ("pass", vec!["otp", id]),
+      (PasswordManager::Bitwarden, false) => ("bw", vec!["get", "password", id]),
+      (PasswordManager::Bitwarden, true) => ("bw", vec!["get", "totp", id]),
+      (PasswordManager::OnePassword, false) => ("op", vec!["item", "get", id, "--fields", "password", "--reveal"]),
+      (PasswordManager::OnePassword, true) => ("op", vec!["item", "get", id, "--otp"]),
+    };
+    (program, args.into_iter().map(String::from).collect())
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for PasswordsProvider {
+  fn name(&self) -> &'static str {
+    "passwords"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let (otp, name) = match (query.strip_prefix("pw "), query.strip_prefix("otp ")) {
+      (Some(name), _) => (false, name),
+      (_, Some(name)) => (true, name),
+      _ => return vec![],
+    };
+    let name = name.trim().to_lowercase();
+    self.entries
+      .get_or_init(|| self.list())
+      .iter()
+      .filter(|(_, entry)| entry.to_lowercase().contains(&name))
+      .map(|(id, entry)| {
+        let (program, args) = self.secret_command(id, otp);
+        let what = if otp { "one-time code" } else { "password" };
+        Shortcut {
+          path: program.to_string(),
+          args,
+          seq: vec![entry.to_lowercase()],
+          description: Some(format!("copy {what} of {entry}")),
+          kind: ShortcutKind::Secret,
+          clear_after: Some(self.config.clear_after),
+          ..Shortcut::default()
+        }
+      })
+      .collect()
+  }
+}
+
+/// Open windows, listed with `wmctrl` on X11, PowerShell on Windows and System Events on macOS
+#[cfg(feature = "providers")]
+struct WindowsProvider;
+
+#[cfg(feature = "providers")]
+impl WindowsProvider {
+  /// Window ids and titles
+  fn list() -> Vec<(String, String)> {
+    let output = if cfg!(windows) {
+      Command::new("powershell")
+        .args([
+          "-NoProfile",
+          "-Command",
+          "Get-Process | Where-Object { $_.MainWindowTitle } | ForEach-Object { \"$($_.Id)`t$($_.MainWindowTitle)\" }",
+        ])
+        .output()
+    } else if cfg!(target_os = "macos") {
+      Command::new("osascript")
+        .args([
+          "-e",
+          "set out to \"\"\ntell application \"System Events\"\nrepeat with p in (every process whose visible is true)\nrepeat with w in (every window of p)\nset out to out & name of p & tab & name of w & linefeed\nend repeat\nend repeat\nend tell\nreturn out",
+        ])
+        .output()
+    } else {
+      Command::new("wmctrl").arg("-l").output()
+    };
+    let Ok(output) = output else {
+      return vec![];
+    };
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| {
+        if cfg!(any(windows, target_os = "macos")) {
+          let (id, title) = line.split_once('\t')?;
+          Some((id.to_string(), title.to_string()))
+        } else {
+          // 0x03400007  0 hostname Title of the window
+          let mut fields = line.split_whitespace();
+          let id = fields.next()?;
+          let _desktop = fields.next()?;
+          let _host = fields.next()?;
+          Some((id.to_string(), fields.collect::<Vec<_>>().join(" ")))
+        }
+      })
+      .filter(|(_, title)| !title.is_empty())
+      .collect()
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for WindowsProvider {
+  fn name(&self) -> &'static str {
+    "windows"
+  }
+  fn prefix(&self) -> Option<&'static str> {
+    Some("w:")
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    Self::list()
+      .into_iter()
+      .map(|(id, title)| Shortcut {
+        path: id,
+        seq: vec![title.to_lowercase()],
+        description: Some(title),
+        kind: ShortcutKind::Window,
+        ..Shortcut::default()
+      })
+      .collect()
+  }
+}
+
+/// Recursive descent evaluation of `+ - * / % ^` and parentheses over decimal numbers
+struct Calculator<'a> {
+  chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Calculator<'_> {
+  /// Value of `expression`, if it is arithmetic with at least one operator
+  fn evaluate(expression: &str) -> Option<f64> {
+    if !expression.contains(['+', '-', '*', '/', '%', '^']) {
+      return None;
+    }
+    Calculator::value(expression)
+  }
+  /// Value of `expression`, which may also be a plain number
+  fn value(expression: &str) -> Option<f64> {
+    let expression = expression.trim();
+    let mut calculator = Calculator { chars: expression.chars().peekable() };
+    let value = calculator.sum()?;
+    calculator.skip_spaces();
+    if calculator.chars.peek().is_some() || !value.is_finite() {
+      return None;
+    }
+    Some(value)
+  }
+  fn skip_spaces(&mut self) {
+    while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+  }
+  fn next_operator(&mut self, operators: &[char]) -> Option<char> {
+    self.skip_spaces();
+    self.chars.next_if(|c| operators.contains(c))
+  }
+  fn sum(&mut self) -> Option<f64> {
+    let mut value = self.product()?;
+    while let Some(operator) = self.next_operator(&['+', '-']) {
+      let rhs = self.product()?;
+      value = if operator == '+' { value + rhs } else { value - rhs };
+    }
+    Some(value)
+  }
+  fn product(&mut self) -> Option<f64> {
+    let mut value = self.power()?;
+    while let Some(operator) = self.next_operator(&['*', '/', '%']) {
+      let rhs = self.power()?;
+      value = match operator {
+        '*' => value * rhs,
+        '/' => value / rhs,
+        _ => value % rhs,
+      };
+    }
+    Some(value)
+  }
+  fn power(&mut self) -> Option<f64> {
+    let base = self.unary()?;
+    match self.next_operator(&['^']) {
+      Some(_) => Some(base.powf(self.power()?)),
+      None => Some(base),
+    }
+  }
+  fn unary(&mut self) -> Option<f64> {
+    match self.next_operator(&['-', '+']) {
+      Some('-') => Some(-self.unary()?),
+      Some(_) => self.unary(),
+      None => self.atom(),
+    }
+  }
+  fn atom(&mut self) -> Option<f64> {
+    self.skip_spaces();
+    if self.chars.next_if_eq(&'(').is_some() {
+      let value = self.sum()?;
+      return self.next_operator(&[')']).map(|_| value);
+    }
+    let mut number = String::new();
+    while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.' || *c == '_') {
+      if c != '_' {
+        number.push(c);
+      }
+    }
+    number.parse().ok()
+  }
+}
+
+/// Formats a result without float noise like `0.30000000000000004`
+fn format_number(value: f64) -> String {
+  let rounded = format!("{value:.10}");
+  let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+  if trimmed == "-0" {
+    "0".to_string()
+  } else {
+    trimmed.to_string()
+  }
+}
+
+/// Units by their names, with their dimension and how many of the dimension's base unit they are
+const UNITS: &[(&[&str], &str, f64)] = &[
+  (&["mm", "millimeter", "millimeters"], "length", 0.001),
+  (&["cm", "centimeter", "centimeters"], "length", 0.01),
+  (&["m", "meter", "meters"], "length", 1.0),
+  (&["km", "kilometer", "kilometers"], "length", 1000.0),
+  (&["in", "inch", "inches"], "length", 0.0254),
+  (&["ft", "foot", "feet"], "length", 0.3048),
+  (&["yd", "yard", "yards"], "length", 0.9144),
+  (&["mi", "mile", "miles"], "length", 1609.344),
+  (&["nmi"], "length", 1852.0),
+  (&["mg", "milligram", "milligrams"], "mass", 0.001),
+  (&["g", "gram", "grams"], "mass", 1.0),
+  (&["kg", "kilogram", "kilograms"], "mass", 1000.0),
+  (&["t", "tonne", "tonnes"], "mass", 1_000_000.0),
+  (&["oz", "ounce", "ounces"], "mass", 28.349523125),
+  (&["lb", "lbs", "pound", "pounds"], "mass", 453.59237),
+  (&["st", "stone"], "mass", 6350.29318),
+  (&["ml", "milliliter", "milliliters"], "volume", 0.001),
+  (&["l", "liter", "liters"], "volume", 1.0),
+  (&["floz"], "volume", 0.0295735295625),
+  (&["cup", "cups"], "volume", 0.2365882365),
+  (&["pt", "pint", "pints"], "volume", 0.473176473),
+  (&["qt", "quart", "quarts"], "volume", 0.946352946),
+  (&["gal", "gallon", "gallons"], "volume", 3.785411784),
+  (&["ms", "millisecond", "milliseconds"], "time", 0.001),
+  (&["s", "sec", "second", "seconds"], "time", 1.0),
+  (&["min", "minute", "minutes"], "time", 60.0),
+  (&["h", "hr", "hour", "hours"], "time", 3600.0),
+  (&["d", "day", "days"], "time", 86400.0),
+  (&["wk", "week", "weeks"], "time", 604800.0),
+  (&["b", "byte", "bytes"], "data", 1.0),
+  (&["kb"], "data", 1e3),
+  (&["mb"], "data", 1e6),
+  (&["gb"], "data", 1e9),
+  (&["tb"], "data", 1e12),
+  (&["kib"], "data", 1024.0),
+  (&["mib"], "data", 1048576.0),
+  (&["gib"], "data", 1073741824.0),
+  (&["tib"], "data", 1099511627776.0),
+  (&["m/s", "mps"], "speed", 1.0),
+  (&["km/h", "kmh", "kph"], "speed", 1.0 / 3.6),
+  (&["mph"], "speed", 0.44704),
+  (&["kn", "knot", "knots"], "speed", 0.514444),
+];
+
+/// Converts between temperature scales through kelvin, they are offset rather than scaled
+fn convert_temperature(value: f64, from: &str, to: &str) -> Option<f64> {
+  let kelvin = match from {
+    "c" | "°c" | "celsius" => value + 273.15,
+    "f" | "°f" | "fahrenheit" => ((value - 32.0) * 5.0) / 9.0 + 273.15,
+    "k" | "kelvin" => value,
+    _ => {
+      return None;
+    }
+  };
+  match to {
+    "c" | "°c" | "celsius" => Some(kelvin - 273.15),
+    "f" | "°f" | "fahrenheit" => Some(((kelvin - 273.15) * 9.0) / 5.0 + 32.0),
+    "k" | "kelvin" => Some(kelvin),
+    _ => None,
+  }
+}
+
+fn convert_unit(value: f64, from: &str, to: &str) -> Option<f64> {
+  let find = |name: &str| UNITS.iter().find(|(names, _, _)| names.contains(&name));
+  match (find(from), find(to)) {
+    (Some((_, from_dimension, from_factor)), Some((_, to_dimension, to_factor))) if
+      from_dimension == to_dimension
+    => Some((value * from_factor) / to_factor),
+    _ => convert_temperature(value, from, to),
+  }
+}
+
+/// Exchange rates relative to a base currency
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CurrencyRates {
+  #[serde(alias = "base_code")]
+  base: String,
+  rates: std::collections::HashMap<String, f64>,
+}
+
+impl CurrencyRates {
+  fn convert(&self, value: f64, from: &str, to: &str) -> Option<f64> {
+    let rate = |code: &str| {
+      let code = code.to_uppercase();
+      if code == self.base { Some(1.0) } else { self.rates.get(&code).copied() }
+    };
+    Some((value / rate(from)?) * rate(to)?)
+  }
+  fn cache_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("currency_rates.json"))
+  }
+  fn fetch(url: &str) -> Result<CurrencyRates, String> {
+    let body = http_get(url)?;
+    serde_json::from_str(&body).map_err(|e| e.to_string())
+  }
+  /// Rates from the configured file, or the cache while it is younger than a day.
+  /// A stale or missing cache is refreshed in the background and filled in when the download is done
+  fn load(config: &CurrencyConfig) -> std::sync::Arc<std::sync::Mutex<Option<CurrencyRates>>> {
+    let read = |path: &Path| {
+      fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<CurrencyRates>(&c).ok())
+    };
+    if let Some(file) = &config.rates_file {
+      return std::sync::Arc::new(std::sync::Mutex::new(read(Path::new(file))));
+    }
+    let cache_path = CurrencyRates::cache_path();
+    let cached = cache_path.as_deref().and_then(read);
+    let fresh = cache_path
+      .as_deref()
+      .and_then(|p| p.metadata().and_then(|m| m.modified()).ok())
+      .and_then(|modified| modified.elapsed().ok())
+      .is_some_and(|age| age < Duration::from_secs(24 * 60 * 60));
+    let rates = std::sync::Arc::new(std::sync::Mutex::new(cached));
+    if let (false, Some(url), Some(cache_path)) = (fresh, config.rates_url.clone(), cache_path) {
+      let shared = rates.clone();
+      std::thread::spawn(move || {
+        match CurrencyRates::fetch(&url) {
+          Ok(fetched) => {
+            if let Ok(content) = serde_json::to_string(&fetched) {
+              write_cache(&cache_path, &content);
+            }
+            *shared.lock().unwrap() = Some(fetched);
+          }
+          Err(e) => error!(url, "failed to fetch currency rates: {e}"),
+        }
+      });
+    }
+    rates
+  }
+}
+
+/// Shows the value of an arithmetic query, or of a conversion like `12 km to mi`, copied with Enter
+struct CalculatorProvider {
+  rates: std::sync::Arc<std::sync::Mutex<Option<CurrencyRates>>>,
+}
+
+impl CalculatorProvider {
+  /// Splits `<amount> <unit> to|in <unit>` into its value and lowercased units
+  fn parse_conversion(query: &str) -> Option<(f64, String, String)> {
+    let query = query.trim().to_lowercase();
+    let (left, to) = query.rsplit_once(" to ").or_else(|| query.rsplit_once(" in "))?;
+    let left = left.trim();
+    let unit_start = left
+      .char_indices()
+      .rev()
+      .take_while(|(_, c)| c.is_alphabetic() || *c == '/' || *c == '°')
+      .last()?.0;
+    let (amount, from) = left.split_at(unit_start);
+    Some((Calculator::value(amount)?, from.to_string(), to.trim().to_string()))
+  }
+  fn convert(&self, query: &str) -> Option<(f64, String)> {
+    let (value, from, to) = Self::parse_conversion(query)?;
+    let converted = convert_unit(value, &from, &to).or_else(|| {
+      let rates = self.rates.lock().unwrap();
+      rates.as_ref()?.convert(value, &from, &to)
+    })?;
+    Some((converted, to))
+  }
+}
+
+impl Provider for CalculatorProvider {
+  fn name(&self) -> &'static str {
+    "calculator"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let (result, description) = match Calculator::evaluate(query) {
+      Some(value) => {
+        let result = format_number(value);
+        (result.clone(), format!("{} = {result}", query.trim()))
+      }
+      None => {
+        let Some((value, unit)) = self.convert(query) else {
+          return vec![];
+        };
+        let result = format_number(value);
+        let left = query.trim().rsplit_once(" to ").or_else(|| query.trim().rsplit_once(" in "));
+        (result.clone(), format!("{} = {result} {unit}", left.map(|(l, _)| l).unwrap_or(query).trim()))
+      }
+    };
+    vec![Shortcut {
+      seq: vec![result.clone()],
+      description: Some(description),
+      path: result,
+      kind: ShortcutKind::Snippet,
+      ..Shortcut::default()
+    }]
+  }
+}
+
+#[cfg(feature = "providers")]
+struct RunningProcess {
+  pid: u32,
+  name: String,
+  /// Owned by root or another user on unix, or running in the services session on Windows
+  system: bool,
+}
+
+/// Lists running processes with `kill <name>` and terminates the chosen one,
+/// asking first when it belongs to the system
+#[cfg(feature = "providers")]
+struct ProcessesProvider;
+
+#[cfg(feature = "providers")]
+impl ProcessesProvider {
+  fn list() -> Vec<RunningProcess> {
+    if cfg!(windows) {
+      let Ok(output) = Command::new("tasklist").args(["/fo", "csv", "/nh"]).output() else {
+        return vec![];
+      };
+      // "name.exe","1234","Console","1","10,000 K"
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+          let fields: Vec<&str> = line.split("\",\"").map(|f| f.trim_matches('"')).collect();
+          Some(RunningProcess {
+            name: fields.first()?.to_string(),
+            pid: fields.get(1)?.parse().ok()?,
+            system: fields.get(2)? == &"Services",
+          })
+        })
+        .collect()
+    } else {
+      let Ok(output) = Command::new("ps").args(["-axo", "pid=,uid=,comm="]).output() else {
+        return vec![];
+      };
+      let own_uid = Command::new("id")
+        .arg("-u")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+          let mut fields = line.split_whitespace();
+          let pid = fields.next()?.parse().ok()?;
+          let uid = fields.next()?;
+          let command = fields.collect::<Vec<_>>().join(" ");
+          Some(RunningProcess {
+            pid,
+            name: Path::new(&command).file_name()?.to_str()?.to_string(),
+            system: uid == "0" || own_uid.as_deref().is_some_and(|own| own != uid),
+          })
+        })
+        .filter(|p| p.pid != std::process::id())
+        .collect()
+    }
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for ProcessesProvider {
+  fn name(&self) -> &'static str {
+    "processes"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let Some(name) = query.strip_prefix("kill ").map(|n| n.trim().to_lowercase()) else {
+      return vec![];
+    };
+    Self::list()
+      .into_iter()
+      .filter(|p| p.name.to_lowercase().contains(&name))
+      .map(|p| Shortcut {
+        path: p.pid.to_string(),
+        seq: vec![p.name.to_lowercase()],
+        description: Some(
+          format!("{} ({}{})", p.name, p.pid, if p.system { ", system" } else { "" })
+        ),
+        kind: ShortcutKind::Process,
+        confirm: p.system,
+        ..Shortcut::default()
+      })
+      .collect()
+  }
+}
+
+/// Whether `name` matches a glob `pattern` where `*` matches any characters and `?` one
+#[cfg(feature = "providers")]
+fn glob_match(pattern: &str, name: &str) -> bool {
+  let (pattern, name): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+  let (mut p, mut n) = (0, 0);
+  // position of the last `*` and the name position it is matched up to
+  let mut star: Option<(usize, usize)> = None;
+  while n < name.len() {
+    if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+      p += 1;
+      n += 1;
+    } else if p < pattern.len() && pattern[p] == '*' {
+      star = Some((p, n));
+      p += 1;
+    } else if let Some((star_p, star_n)) = star {
+      p = star_p + 1;
+      n = star_n + 1;
+      star = Some((star_p, star_n + 1));
+    } else {
+      return false;
+    }
+  }
+  pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Files and folders under the configured roots, searched behind `/`.
+/// The index is built in the background and persisted, so the previous one is searched meanwhile
+#[cfg(feature = "providers")]
+struct FileIndexProvider {
+  index: std::sync::Arc<std::sync::Mutex<Vec<PathBuf>>>,
+}
+
+#[cfg(feature = "providers")]
+impl FileIndexProvider {
+  /// Most results shown for a query
+  const LIMIT: usize = 50;
+
+  fn new(config: &FileIndexConfig) -> Self {
+    let cached = Self::cache_path()
+      .and_then(|p| fs::read_to_string(p).ok())
+      .and_then(|c| serde_json::from_str::<Vec<PathBuf>>(&c).ok())
+      .unwrap_or_default();
+    let index = std::sync::Arc::new(std::sync::Mutex::new(cached));
+    let (shared, config) = (index.clone(), config.clone());
+    std::thread::spawn(move || {
+      let mut paths = vec![];
+      for root in &config.roots {
+        Self::walk(Path::new(root), &config.ignore, &mut paths);
+      }
+      debug!(files = paths.len(), "built file index");
+      // json only holds UTF-8 paths, the others are back once the index is built again
+      let cached: Vec<&PathBuf> = paths.iter().filter(|path| path.to_str().is_some()).collect();
+      if let (Some(path), Ok(content)) = (Self::cache_path(), serde_json::to_string(&cached)) {
+        write_cache(&path, &content);
+      }
+      *shared.lock().unwrap() = paths;
+    });
+    FileIndexProvider { index }
+  }
+  fn cache_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("file_index.json"))
+  }
+  /// Patterns of the `.gitignore` and `.ignore` files in `dir`, matched against names only
+  fn ignore_files(dir: &Path) -> Vec<String> {
+    [".gitignore", ".ignore"]
+      .iter()
+      .filter_map(|file| fs::read_to_string(dir.join(file)).ok())
+      .flat_map(|content| {
+        content
+          .lines()
+          .map(str::trim)
+          .filter(|line| !line.is_empty() && !line.starts_with(['#', '!']))
+          .map(|line| line.trim_matches('/').to_string())
+          .filter(|line| !line.contains('/'))
+          .collect::<Vec<_>>()
+      })
+      .collect()
+  }
+  fn walk(dir: &Path, ignore: &[String], paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+      return;
+    };
+    let mut ignore = ignore.to_vec();
+    ignore.extend(Self::ignore_files(dir));
+    for path in entries.flatten().map(|e| e.path()) {
+      let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        continue;
+      };
+      let name = name.as_ref();
+      if name == ".git" || ignore.iter().any(|pattern| glob_match(pattern, name)) {
+        continue;
+      }
+      paths.push(path.clone());
+      if path.is_dir() && !path.is_symlink() {
+        Self::walk(&path, &ignore, paths);
+      }
+    }
+  }
+}
+
+#[cfg(feature = "providers")]
+impl Provider for FileIndexProvider {
+  fn name(&self) -> &'static str {
+    "file_index"
+  }
+  fn shortcuts(&self) -> Vec<Shortcut> {
+    vec![]
+  }
+  fn prefix(&self) -> Option<&'static str> {
+    Some("/")
+  }
+  fn search(&self, query: &str) -> Vec<Shortcut> {
+    let Some(name) = query.strip_prefix('/').map(str::to_lowercase).filter(|n| !n.is_empty()) else {
+      return vec![];
+    };
+    self.index
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|path| {
+        path
+          .file_name()
+          .is_some_and(|n| n.to_string_lossy().to_lowercase().contains(&name))
+      })
+      .take(Self::LIMIT)
+      .filter_map(|path| {
+        Some(
+          Shortcut {
+            path: path.to_string_lossy().to_string(),
+            seq: vec![path.file_name()?.to_string_lossy().to_lowercase()],
+            description: Some(path.to_string_lossy().to_string()),
+            kind: if path.is_dir() { ShortcutKind::Dir } else { ShortcutKind::File },
+            ..Shortcut::default()
+          }.with_os_path(path)
+        )
+      })
+      .collect()
+  }
+}
+
+/// The config provider followed by every provider enabled in `providers`
+pub fn build_providers(config: &Config) -> Vec<Box<dyn Provider>> {
+  let mut providers: Vec<Box<dyn Provider>> = vec![
+    Box::new(ConfigProvider { shortcuts: config.shortcuts.clone() })
+  ];
+  let searches: Vec<Shortcut> = config.shortcuts.iter().filter(|s| s.is_search()).cloned().collect();
+  if !searches.is_empty() {
+    let suggestions = config.providers.web_suggestions.then(WebSuggestions::default);
+    providers.push(Box::new(WebSearchProvider { searches, suggestions }));
+  }
+  if !config.bangs.is_empty() {
+    let bangs = config.bangs
+      .iter()
+      .map(|(name, url)| (name.trim_start_matches('!').to_string(), url.clone()))
+      .collect();
+    providers.push(Box::new(BangsProvider { bangs }));
+  }
+  let templates: Vec<Shortcut> = config.shortcuts.iter().filter(|s| s.is_template()).cloned().collect();
+  if !templates.is_empty() {
+    providers.push(Box::new(TemplatesProvider { templates }));
+  }
+  let notes: Vec<Shortcut> = config.shortcuts.iter().filter(|s| s.kind == ShortcutKind::Note).cloned().collect();
+  if !notes.is_empty() {
+    providers.push(Box::new(NotesProvider { notes }));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.start_menu && cfg!(windows) {
+    providers.push(Box::new(StartMenuProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.desktop_entries && cfg!(all(unix, not(target_os = "macos"))) {
+    providers.push(Box::new(DesktopEntriesProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.applications && cfg!(target_os = "macos") {
+    providers.push(Box::new(MacApplicationsProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.path_executables {
+    providers.push(Box::new(PathExecutablesProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.bookmarks {
+    providers.push(Box::new(BookmarksProvider));
+  }
+  #[cfg(feature = "providers")]
+  if let Some(git_repos) = &config.providers.git_repos {
+    providers.push(Box::new(GitReposProvider { config: git_repos.clone() }));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.vscode_recent {
+    providers.push(Box::new(VsCodeRecentProvider));
+  }
+  #[cfg(feature = "providers")]
+  for external in &config.providers.external {
+    providers.push(Box::new(ExternalProvider { config: external.clone() }));
+  }
+  #[cfg(feature = "providers")]
+  if let Some(passwords) = &config.providers.passwords {
+    providers.push(Box::new(PasswordsProvider { config: passwords.clone(), entries: Default::default() }));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.system_actions {
+    providers.push(Box::new(SystemActionsProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.services && cfg!(target_os = "linux") {
+    providers.push(Box::new(ServicesProvider));
+  }
+  #[cfg(feature = "docker")]
+  if config.providers.docker {
+    providers.push(Box::new(DockerProvider));
+  }
+  #[cfg(not(feature = "docker"))]
+  if config.providers.docker {
+    error!("docker provider is enabled, but bullet was built without the docker feature");
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.ssh_hosts {
+    providers.push(Box::new(SshHostsProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.tmux {
+    providers.push(Box::new(TmuxProvider));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.windows {
+    providers.push(Box::new(WindowsProvider));
+  }
+  if config.providers.calculator {
+    let rates = match &config.providers.currency {
+      Some(currency) => CurrencyRates::load(currency),
+      None => std::sync::Arc::new(std::sync::Mutex::new(None)),
+    };
+    providers.push(Box::new(CalculatorProvider { rates }));
+  }
+  #[cfg(feature = "providers")]
+  if config.providers.processes {
+    providers.push(Box::new(ProcessesProvider));
+  }
+  #[cfg(feature = "providers")]
+  if let Some(file_index) = &config.providers.file_index {
+    providers.push(Box::new(FileIndexProvider::new(file_index)));
+  }
+  providers
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn calculator_evaluates_arithmetic() {
+    assert_eq!(Calculator::evaluate("1 + 2 * 3"), Some(7.0));
+    assert_eq!(Calculator::evaluate("(1 + 2) * 3"), Some(9.0));
+    assert_eq!(Calculator::evaluate("2^-1"), Some(0.5));
+    assert_eq!(Calculator::evaluate("2 ^ 3 ^ 2"), Some(512.0));
+    assert_eq!(Calculator::evaluate("10 % 4"), Some(2.0));
+    assert_eq!(Calculator::evaluate("-3 - -2"), Some(-1.0));
+    assert_eq!(Calculator::evaluate("1_000 / 8"), Some(125.0));
+  }
+
+  #[test]
+  fn calculator_rejects_what_is_not_arithmetic() {
+    assert_eq!(Calculator::evaluate("(1+2"), None);
+    assert_eq!(Calculator::evaluate("1+2)"), None);
+    assert_eq!(Calculator::evaluate("1.2.3 + 1"), None);
+    assert_eq!(Calculator::evaluate("1 / 0"), None);
+    assert_eq!(Calculator::evaluate("1 +"), None);
+    assert_eq!(Calculator::evaluate("42"), None);
+    assert_eq!(Calculator::evaluate("e-mail"), None);
+  }
+
+  #[test]
+  fn format_number_drops_float_noise() {
+    assert_eq!(format_number(0.1 + 0.2), "0.3");
+    assert_eq!(format_number(125.0), "125");
+    assert_eq!(format_number(-0.0), "0");
+    assert_eq!(format_number(-2.5), "-2.5");
+    assert_eq!(format_number(1.0 / 3.0), "0.3333333333");
+  }
+
+  #[test]
+  fn parses_conversions() {
+    let parse = CalculatorProvider::parse_conversion;
+    assert_eq!(parse("12km to mi"), Some((12.0, "km".to_string(), "mi".to_string())));
+    assert_eq!(parse("100 USD in EUR"), Some((100.0, "usd".to_string(), "eur".to_string())));
+    assert_eq!(parse("2*3 °C to F"), Some((6.0, "°c".to_string(), "f".to_string())));
+    assert_eq!(parse("90 km/h to mph"), Some((90.0, "km/h".to_string(), "mph".to_string())));
+    assert_eq!(parse("12 to mi"), None);
+    assert_eq!(parse("km to mi"), None);
+    assert_eq!(parse("12 km"), None);
+  }
+
+  #[test]
+  fn converts_units_of_the_same_dimension() {
+    let close = |value: Option<f64>, expected: f64| (value.unwrap() - expected).abs() < 1e-9;
+    assert!(close(convert_unit(12.0, "km", "mi"), 7.456454306848007));
+    assert!(close(convert_unit(1.0, "gib", "mb"), 1073.741824));
+    assert!(close(convert_unit(100.0, "c", "f"), 212.0));
+    assert!(close(convert_unit(0.0, "k", "celsius"), -273.15));
+    assert_eq!(convert_unit(1.0, "km", "kg"), None);
+    assert_eq!(convert_unit(100.0, "usd", "eur"), None);
+  }
+
+  #[test]
+  fn converts_currencies_through_the_base() {
+    let rates = CurrencyRates { base: "EUR".to_string(), rates: [("USD".to_string(), 2.0), ("GBP".to_string(), 0.5)].into() };
+    let provider = CalculatorProvider { rates: std::sync::Arc::new(std::sync::Mutex::new(Some(rates))) };
+    assert_eq!(provider.convert("100 usd in eur"), Some((50.0, "eur".to_string())));
+    assert_eq!(provider.convert("100 usd to gbp"), Some((25.0, "gbp".to_string())));
+    assert_eq!(provider.convert("100 usd to xyz"), None);
+  }
+
+  #[cfg(feature = "providers")]
+  #[test]
+  fn reads_the_hosts_of_an_ssh_config() {
+    let config = "
+      # work
+      Host prod prod-eu
+        HostName 10.0.0.1
+        User deploy
+      Host *.internal !bastion
+        User me
+      Host=home
+        User=pi
+      Match host build
+        HostName build.example
+      host   plain
+        hostname plain.example
+    ";
+    assert_eq!(
+      SshHostsProvider::config_hosts(config),
+      [
+        ("prod".to_string(), Some("deploy@10.0.0.1".to_string())),
+        ("prod-eu".to_string(), Some("deploy@10.0.0.1".to_string())),
+        ("home".to_string(), Some("pi@home".to_string())),
+        ("plain".to_string(), Some("plain.example".to_string())),
+      ]
+    );
+  }
+
+  #[cfg(feature = "providers")]
+  #[test]
+  fn reads_the_hosts_of_known_hosts() {
+    let known_hosts = "
+      github.com,140.82.121.4 ssh-ed25519 AAAA
+      [git.example]:2222 ssh-ed25519 AAAA
+      192.168.1.2 ssh-ed25519 AAAA
+      |1|hashed= ssh-ed25519 AAAA
+      @revoked old.example ssh-rsa AAAA
+    ";
+    assert_eq!(SshHostsProvider::known_hosts(known_hosts), ["github.com", "ssh://git.example:2222"]);
+  }
+
+  #[cfg(feature = "providers")]
+  #[test]
+  fn matches_globs() {
+    assert!(glob_match("*.rs", "main.rs"));
+    assert!(glob_match("*.rs", ".rs"));
+    assert!(!glob_match("*.rs", "main.rsx"));
+    assert!(glob_match("node_modules", "node_modules"));
+    assert!(glob_match("?ar*et", "target"));
+    assert!(glob_match("a*b*c", "axxbyybzc"));
+    assert!(!glob_match("a*b*c", "axxbyy"));
+    assert!(glob_match("*", ""));
+    assert!(!glob_match("?", ""));
+    assert!(glob_match("ünï*", "ünïcode"));
+  }
+
+  #[cfg(all(feature = "providers", unix))]
+  #[test]
+  fn kills_an_external_provider_that_does_not_answer_in_time() {
+    let external = |script: &str| ExternalProvider {
+      config: ExternalProviderConfig {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), script.to_string()],
+        prefix: None,
+        timeout: 200,
+      },
+    };
+    let answered = external(r#"read q; echo "[{\"title\": \"$q\", \"action\": \"https://a.example\"}]""#).run("hi\n");
+    assert_eq!(answered.unwrap()[0].title, "hi");
+    let started = std::time::Instant::now();
+    assert!(external("sleep 10").run("").is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+  }
+}
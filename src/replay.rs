@@ -0,0 +1,122 @@
+//! `bullet --replay`: scripted keys fed to the picker in place of the keyboard, for reproducible
+//! demos and for driving the ui in tests
+//!
+//! A script has one step per line. Blank lines are skipped and ` #` starts a comment, as does a `#`
+//! at the start of a line:
+//!
+//! ```text
+//! delay 80        # wait 80ms after each of the keys below
+//! type notes      # types each character
+//! wait 500        # waits half a second, the results keep coming in
+//! key down        # presses a key, with modifiers like ctrl+y or shift+enter
+//! key enter
+//! ```
+
+use std::{ collections::VecDeque, path::Path, time::Duration };
+use crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+
+/// One step of a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayStep {
+  Key(KeyEvent),
+  Wait(Duration),
+}
+
+/// The steps of a script, taken in order until they run out
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+  steps: VecDeque<ReplayStep>,
+}
+
+impl Replay {
+  pub fn parse(script: &str) -> Result<Replay, String> {
+    let mut steps = VecDeque::new();
+    let mut delay = Duration::ZERO;
+    for (number, line) in script.lines().enumerate() {
+      let line = line.split_once(" #").map_or(line, |(step, _)| step).trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+      let keys = match command {
+        "type" => argument.chars().map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)).collect(),
+        "key" => vec![parse_key(argument.trim()).map_err(|e| format!("line {}: {e}", number + 1))?],
+        "wait" | "delay" => {
+          let millis = argument
+            .trim()
+            .parse()
+            .map_err(|_| format!("line {}: \"{argument}\" is not a number of milliseconds", number + 1))?;
+          if command == "wait" {
+            steps.push_back(ReplayStep::Wait(Duration::from_millis(millis)));
+          } else {
+            delay = Duration::from_millis(millis);
+          }
+          vec![]
+        }
+        command => {
+          return Err(format!("line {}: \"{command}\" is not type, key, wait or delay", number + 1));
+        }
+      };
+      for key in keys {
+        steps.push_back(ReplayStep::Key(key));
+        if !delay.is_zero() {
+          steps.push_back(ReplayStep::Wait(delay));
+        }
+      }
+    }
+    Ok(Replay { steps })
+  }
+  pub fn load(path: &Path) -> std::io::Result<Replay> {
+    let script = std::fs::read_to_string(path)?;
+    Replay::parse(&script).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+  }
+}
+
+impl Iterator for Replay {
+  type Item = ReplayStep;
+  fn next(&mut self) -> Option<ReplayStep> {
+    self.steps.pop_front()
+  }
+}
+
+/// A key like `enter`, `pagedown`, `f5` or `x`, after `ctrl+`, `alt+` and `shift+` modifiers
+fn parse_key(key: &str) -> Result<KeyEvent, String> {
+  let mut modifiers = KeyModifiers::NONE;
+  let mut name = key;
+  while let Some((modifier, rest)) = name.split_once('+').filter(|(_, rest)| !rest.is_empty()) {
+    modifiers |= match modifier.to_lowercase().as_str() {
+      "ctrl" => KeyModifiers::CONTROL,
+      "alt" => KeyModifiers::ALT,
+      "shift" => KeyModifiers::SHIFT,
+      _ => {
+        return Err(format!("\"{modifier}\" is not ctrl, alt or shift"));
+      }
+    };
+    name = rest;
+  }
+  let code = match name.to_lowercase().as_str() {
+    _ if name.chars().count() == 1 => KeyCode::Char(name.chars().next().unwrap()),
+    "enter" => KeyCode::Enter,
+    "esc" => KeyCode::Esc,
+    "tab" => KeyCode::Tab,
+    "backtab" => KeyCode::BackTab,
+    "backspace" => KeyCode::Backspace,
+    "delete" => KeyCode::Delete,
+    "space" => KeyCode::Char(' '),
+    "up" => KeyCode::Up,
+    "down" => KeyCode::Down,
+    "left" => KeyCode::Left,
+    "right" => KeyCode::Right,
+    "home" => KeyCode::Home,
+    "end" => KeyCode::End,
+    "pageup" => KeyCode::PageUp,
+    "pagedown" => KeyCode::PageDown,
+    other => match other.strip_prefix('f').and_then(|number| number.parse().ok()) {
+      Some(number) => KeyCode::F(number),
+      None => {
+        return Err(format!("\"{name}\" is not a key"));
+      }
+    },
+  };
+  Ok(KeyEvent::new(code, modifiers))
+}
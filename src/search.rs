@@ -0,0 +1,71 @@
+//! Matching shortcuts against what is typed
+
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+use crate::shortcut::{ SharedShortcut, ShortcutKind };
+
+/// How many of the last components of a File or Dir path are matched, the name and its parents
+const PATH_SEGMENTS: usize = 3;
+
+pub trait ShortcutsTrait {
+  fn find(&self, search: String) -> Vec<SharedShortcut>;
+}
+
+impl ShortcutsTrait for [SharedShortcut] {
+  fn find(&self, search: String) -> Vec<SharedShortcut> {
+    if search.trim().is_empty() {
+      return self.to_vec();
+    }
+    let search = fold(&search);
+    // shortcuts only found by their path come after the rest, which name them more closely
+    let mut found = vec![];
+    let mut by_path = vec![];
+    for s in self {
+      if
+        s.seq.iter().any(|seq| folded_contains(seq, &search)) ||
+        s.tags.iter().any(|tag| folded_contains(tag, &search)) ||
+        s.description.as_ref().is_some_and(|description| folded_contains(description, &search))
+      {
+        found.push(s.clone());
+      } else if path_segments(s).any(|segment| folded_contains(segment, &search)) {
+        by_path.push(s.clone());
+      }
+    }
+    found.extend(by_path);
+    found
+  }
+}
+
+/// The last `PATH_SEGMENTS` components of the path of a File or Dir, nothing for other kinds
+fn path_segments(shortcut: &SharedShortcut) -> impl Iterator<Item = &str> {
+  let path = match shortcut.kind {
+    ShortcutKind::File | ShortcutKind::Dir => shortcut.path.as_str(),
+    _ => "",
+  };
+  path
+    .rsplit(['/', '\\'])
+    .filter(|segment| !segment.is_empty())
+    .take(PATH_SEGMENTS)
+}
+
+/// Case folded and NFC normalized text, so `é` typed as one or two code points and any case match
+/// alike, `ß` matching `ss` and a final `ς` matching `σ` too. Decomposed first, as folding a composed
+/// character can leave a different sequence than folding its parts
+pub fn fold(text: &str) -> String {
+  text.nfd().default_case_fold().nfc().collect()
+}
+
+/// Whether `text` contains `folded`, which is already folded. Ascii text is compared in place,
+/// only other text is folded
+pub fn folded_contains(text: &str, folded: &str) -> bool {
+  if folded.is_empty() {
+    return true;
+  }
+  if text.is_ascii() && folded.is_ascii() {
+    return text
+      .as_bytes()
+      .windows(folded.len())
+      .any(|window| window.eq_ignore_ascii_case(folded.as_bytes()));
+  }
+  fold(text).contains(folded)
+}
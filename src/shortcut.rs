@@ -0,0 +1,485 @@
+//! Shortcuts, their kinds and the paths they point to
+
+use core::fmt;
+use std::{
+  fmt::{ Display, Formatter },
+  path::{ Path, PathBuf },
+};
+use clap::ValueEnum;
+use directories::{ BaseDirs, UserDirs };
+use serde::{ Deserialize, Serialize };
+use crate::config::Config;
+
+/// Results are listed grouped by kind, in the order of declaration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, ValueEnum)]
+pub enum ShortcutKind {
+  #[default]
+  #[serde(rename = "app")]
+  App,
+  #[serde(rename = "dir")]
+  Dir,
+  #[serde(rename = "file")]
+  File,
+  #[serde(rename = "url")]
+  Url,
+  /// `path` is a host to connect to with `ssh` in a terminal
+  #[serde(rename = "ssh")]
+  Ssh,
+  /// `path` is a command run with `args` in a terminal
+  #[serde(rename = "terminal")]
+  Terminal,
+  /// `path` identifies an open window that is focused: an X11 window id,
+  /// a process id on Windows or an application name on macOS
+  #[serde(rename = "window")]
+  Window,
+  /// `path` is text that is copied to the clipboard, or typed with `auto_type`, with `{date}`,
+  /// `{date:FORMAT}`, `{clipboard}` and `{uuid}` filled in and `{?name}` asked for
+  #[serde(rename = "snippet")]
+  Snippet,
+  /// `path` is the id of a running process that is terminated
+  #[serde(rename = "process")]
+  Process,
+  /// `path` is a command printing a secret, run with `args`. Its first line is copied to the clipboard
+  #[serde(rename = "secret")]
+  Secret,
+  /// `path` is a Rhai script, or a `.rhai` file holding one, run with the helpers of `script_engine`
+  #[serde(rename = "script")]
+  Script,
+  /// `path` is a text or markdown file what is typed after the seq is appended to, with the time.
+  /// Opened when nothing is typed
+  #[serde(rename = "note")]
+  Note,
+  /// Opens its `items` one after the other, like the editor, URLs and notes of a piece of work.
+  /// `path` is not used
+  #[serde(rename = "session")]
+  Session,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutPathPrefix {
+  #[serde(rename = "documents")]
+  Documents,
+  #[serde(rename = "appdata")]
+  Appdata,
+  #[serde(rename = "home")]
+  Home,
+  #[serde(rename = "desktop")]
+  Desktop,
+  #[serde(rename = "downloads")]
+  Downloads,
+  #[serde(rename = "pictures")]
+  Pictures,
+  #[serde(rename = "music")]
+  Music,
+  #[serde(rename = "videos")]
+  Videos,
+  /// The user's data dir, `~/.local/share` on Linux and the roaming AppData on Windows
+  #[serde(rename = "data")]
+  Data,
+  #[serde(rename = "cache")]
+  Cache,
+  /// The directory the config is in, so a bullet folder carried around can point at its own files
+  #[serde(rename = "config")]
+  Config,
+}
+
+/// What Enter opens of a File
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenTarget {
+  /// The file, with its default application
+  #[serde(rename = "file")]
+  File,
+  /// The folder holding it, with the file selected
+  #[serde(rename = "folder")]
+  Folder,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Shortcut {
+  #[serde(default)]
+  pub path: String,
+  pub seq: Vec<String>,
+  pub description: Option<String>,
+  pub kind: ShortcutKind,
+  pub path_prefix: Option<ShortcutPathPrefix>,
+  /// Arguments an App is started with. When empty the path is opened by the system instead
+  #[serde(default)]
+  pub args: Vec<String>,
+  /// Run the App inside a terminal window
+  #[serde(default)]
+  pub terminal: bool,
+  /// How many copies of an App or Terminal are started, one after the other
+  #[serde(default)]
+  pub count: Option<usize>,
+  /// Milliseconds waited between the copies of `count`
+  #[serde(default)]
+  pub count_delay: Option<u64>,
+  /// Extra words the shortcut is found by, like the bookmark folders it is in
+  #[serde(default)]
+  pub tags: Vec<String>,
+  /// Emoji or Nerd Font glyph shown instead of the glyph of the kind
+  #[serde(default)]
+  pub icon: Option<String>,
+  /// Colour of the glyph and aliases over the colour of the kind, a name like `red` or a hex `#ff0000`
+  #[serde(default)]
+  pub color: Option<String>,
+  /// Heading the shortcut is listed under while the query is empty
+  #[serde(default)]
+  pub category: Option<String>,
+  /// Ask before opening the shortcut
+  #[serde(default)]
+  pub confirm: bool,
+  /// Whether a File opens itself or its folder, over the config's `default_actions`
+  #[serde(default)]
+  pub open: Option<OpenTarget>,
+  /// Seconds after which what a Snippet or Secret copied is cleared from the clipboard
+  #[serde(default)]
+  pub clear_after: Option<u64>,
+  /// Type a Snippet into the window that was focused before bullet instead of copying it, for
+  /// fields that block pasting. Typed by `bullet daemon`, `{cursor}` marks where the cursor ends up
+  #[serde(default)]
+  pub auto_type: bool,
+  /// For a search URL with `{query}`, a URL with `{query}` answering with OpenSearch suggestions
+  #[serde(default)]
+  pub suggest: Option<String>,
+  /// Browser a URL opens in, the config's `browser` or the default browser when not set
+  #[serde(default)]
+  pub browser: Option<Browser>,
+  /// Open a URL in a private window, kept out of the browser's history. Needs a `browser`
+  #[serde(default)]
+  pub incognito: bool,
+  /// What a Session opens, in order
+  #[serde(default)]
+  pub items: Vec<SessionItem>,
+  /// Path found on disk that isn't valid UTF-8, `path` only holds it lossily for display then.
+  /// Not cached, the fresh shortcuts of the provider bring it back
+  #[serde(skip)]
+  pub os_path: Option<PathBuf>,
+  /// The path was not there when the shortcut was collected
+  #[serde(skip)]
+  pub missing: bool,
+}
+
+/// One of the things a Session opens, an app, dir, file, URL or terminal command like the shortcut
+/// of that kind, like `{ "kind": "app", "path": "code", "args": ["~/work/client-x"] }`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SessionItem {
+  pub kind: ShortcutKind,
+  pub path: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  /// Run an App inside a terminal window
+  #[serde(default)]
+  pub terminal: bool,
+  /// Milliseconds waited before it is opened, for the apps before it to start
+  #[serde(default)]
+  pub delay: Option<u64>,
+}
+
+impl SessionItem {
+  /// The shortcut it is opened as, found by its path
+  pub(crate) fn shortcut(&self) -> Shortcut {
+    Shortcut {
+      path: self.path.clone(),
+      seq: vec![self.path.clone()],
+      kind: self.kind,
+      args: self.args.clone(),
+      terminal: self.terminal,
+      ..Shortcut::default()
+    }
+  }
+}
+
+/// A browser, or a profile of one, URLs are opened in instead of the default browser, like
+/// `{ "command": "msedge", "args": ["--profile-directory=Profile 1"] }`. On macOS browsers are
+/// app bundles, `{ "command": "open", "args": ["-na", "Firefox", "--args"] }` starts one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Browser {
+  /// Program of the browser, a path or a name on PATH
+  pub command: String,
+  /// Arguments put before the URL
+  #[serde(default)]
+  pub args: Vec<String>,
+}
+
+impl Browser {
+  /// Flag that opens a private window, found by the name of the program, or of the app it starts
+  /// for the likes of `open`
+  pub fn private_flag(&self) -> Option<&'static str> {
+    const FLAGS: &[(&str, &str)] = &[
+      ("firefox", "--private-window"),
+      ("librewolf", "--private-window"),
+      ("waterfox", "--private-window"),
+      ("edge", "--inprivate"),
+      ("opera", "--private"),
+      ("chrom", "--incognito"),
+      ("brave", "--incognito"),
+      ("vivaldi", "--incognito"),
+    ];
+    let program = Path::new(&self.command)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_lowercase())
+      .unwrap_or_default();
+    let flag = |name: &str| FLAGS.iter().find(|(browser, _)| name.contains(browser)).map(|(_, flag)| *flag);
+    flag(&program).or_else(|| self.args.iter().find_map(|arg| flag(&arg.to_lowercase())))
+  }
+}
+
+impl Shortcut {
+  /// What the shortcut is called in messages and logs, its first seq, or its path when it has none
+  pub fn name(&self) -> &str {
+    self.seq.first().unwrap_or(&self.path)
+  }
+  /// Returns with prefixed path if `path_prefix` is defined, just `path` otherwise
+  pub fn prefixed_path(&self) -> PathBuf {
+    if let Some(os_path) = &self.os_path {
+      return os_path.clone();
+    }
+    match self.path_prefix.and_then(|prefix| prefix.dir()) {
+      // a leading separator would make the path replace the prefix dir rather than go in it
+      Some(dir) => dir.join(normalize_path(self.path.trim_start_matches(['/', '\\']))),
+      None if self.holds_path() => expand_path(&self.path),
+      None => PathBuf::from(&self.path),
+    }
+  }
+  /// `prefixed_path` as text, lossy for paths that aren't valid UTF-8
+  pub fn get_prefixed_path(&self) -> String {
+    if self.os_path.is_none() && self.path_prefix.is_none() && !self.holds_path() {
+      return self.path.clone();
+    }
+    self.prefixed_path().to_string_lossy().to_string()
+  }
+  /// Whether `path` is a filesystem path, rather than a URL, text or the source of a script
+  fn holds_path(&self) -> bool {
+    match self.kind {
+      ShortcutKind::Dir | ShortcutKind::File | ShortcutKind::App | ShortcutKind::Terminal | ShortcutKind::Note => true,
+      ShortcutKind::Script => self.path.ends_with(".rhai"),
+      _ => false,
+    }
+  }
+  /// Keeps `path` in `os_path` if it isn't valid UTF-8
+  #[cfg_attr(not(feature = "providers"), allow(dead_code))]
+  pub(crate) fn with_os_path(mut self, path: &Path) -> Shortcut {
+    if path.to_str().is_none() {
+      self.os_path = Some(path.to_path_buf());
+    }
+    self
+  }
+  /// A Url with `{query}` in its path, searched with `<seq> <query>`
+  pub(crate) fn is_search(&self) -> bool {
+    self.kind == ShortcutKind::Url && has_placeholder(&self.path, "query")
+  }
+  /// A shortcut with `{args}` in its path or args, opened with what is typed after its seq
+  pub(crate) fn is_template(&self) -> bool {
+    has_placeholder(&self.path, "args") || self.args.iter().any(|arg| has_placeholder(arg, "args"))
+  }
+  /// The template filled in with `typed`. URLs get it percent-encoded unless it is `{args:raw}`,
+  /// and an argument that is only `{args}` turns into one argument per word
+  pub(crate) fn with_args(&self, typed: &str) -> Shortcut {
+    let path = if self.kind == ShortcutKind::Url {
+      fill_url(&self.path, "args", typed)
+    } else {
+      fill_raw(&self.path, "args", typed)
+    };
+    let args = self.args
+      .iter()
+      .flat_map(|arg| {
+        if arg == "{args}" {
+          typed.split_whitespace().map(String::from).collect()
+        } else {
+          vec![fill_raw(arg, "args", typed)]
+        }
+      })
+      .collect();
+    Shortcut {
+      path,
+      seq: vec![format!("{} {typed}", self.name())],
+      args,
+      ..self.clone()
+    }
+  }
+  /// Names of the `{?name}` placeholders of a Snippet, each once, asked for before it is copied
+  pub fn snippet_prompts(&self) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    if self.kind == ShortcutKind::Snippet {
+      replace_placeholders(&self.path, |placeholder| {
+        if let Some(name) = placeholder.strip_prefix('?').filter(|name| !names.iter().any(|n| n == name)) {
+          names.push(name.to_string());
+        }
+        None
+      });
+    }
+    names
+  }
+  /// How many times `launch` starts the shortcut, `count` for Apps and Terminals and once otherwise
+  pub(crate) fn copies(&self) -> usize {
+    match self.kind {
+      ShortcutKind::App | ShortcutKind::Terminal => self.count.unwrap_or(1).max(1),
+      _ => 1,
+    }
+  }
+  /// Whether `launch` hands the path to the system's default application for it
+  pub(crate) fn opens_with_default_app(&self) -> bool {
+    match self.kind {
+      ShortcutKind::Dir | ShortcutKind::File | ShortcutKind::Url => !self.terminal,
+      ShortcutKind::App => !self.terminal && self.args.is_empty(),
+      _ => false,
+    }
+  }
+}
+
+impl ShortcutPathPrefix {
+  /// Directories of the prefixes, looked up once since they are needed for every row drawn
+  fn resolved() -> &'static std::collections::HashMap<ShortcutPathPrefix, Option<PathBuf>> {
+    static RESOLVED: std::sync::OnceLock<std::collections::HashMap<ShortcutPathPrefix, Option<PathBuf>>> =
+      std::sync::OnceLock::new();
+    RESOLVED.get_or_init(|| {
+      let user = UserDirs::new();
+      let user_dir = |dir: fn(&UserDirs) -> Option<&Path>| user.as_ref().and_then(dir).map(Path::to_path_buf);
+      let base = BaseDirs::new();
+      let base_dir = |dir: fn(&BaseDirs) -> &Path| base.as_ref().map(|dirs| dir(dirs).to_path_buf());
+      [
+        (ShortcutPathPrefix::Documents, user_dir(UserDirs::document_dir)),
+        (ShortcutPathPrefix::Appdata, base_dir(BaseDirs::config_dir)),
+        (ShortcutPathPrefix::Home, base_dir(BaseDirs::home_dir)),
+        (ShortcutPathPrefix::Desktop, user_dir(UserDirs::desktop_dir)),
+        (ShortcutPathPrefix::Downloads, user_dir(UserDirs::download_dir)),
+        (ShortcutPathPrefix::Pictures, user_dir(UserDirs::picture_dir)),
+        (ShortcutPathPrefix::Music, user_dir(UserDirs::audio_dir)),
+        (ShortcutPathPrefix::Videos, user_dir(UserDirs::video_dir)),
+        (ShortcutPathPrefix::Data, base_dir(BaseDirs::data_dir)),
+        (ShortcutPathPrefix::Cache, base_dir(BaseDirs::cache_dir)),
+        (ShortcutPathPrefix::Config, Config::dir()),
+      ]
+        .into_iter()
+        .collect()
+    })
+  }
+}
+
+impl ShortcutPathPrefix {
+  pub(crate) fn name(&self) -> &'static str {
+    match self {
+      ShortcutPathPrefix::Documents => "documents",
+      ShortcutPathPrefix::Appdata => "appdata",
+      ShortcutPathPrefix::Home => "home",
+      ShortcutPathPrefix::Desktop => "desktop",
+      ShortcutPathPrefix::Downloads => "downloads",
+      ShortcutPathPrefix::Pictures => "pictures",
+      ShortcutPathPrefix::Music => "music",
+      ShortcutPathPrefix::Videos => "videos",
+      ShortcutPathPrefix::Data => "data",
+      ShortcutPathPrefix::Cache => "cache",
+      ShortcutPathPrefix::Config => "config",
+    }
+  }
+  /// Directory of the prefix, `None` on systems that don't have it
+  pub(crate) fn dir(&self) -> Option<&'static Path> {
+    ShortcutPathPrefix::resolved()[self].as_deref()
+  }
+}
+
+/// The directory, or `<name>` if the system doesn't have it so the row shows what is missing
+impl Display for ShortcutPathPrefix {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.dir() {
+      Some(dir) => write!(f, "{}", dir.to_string_lossy().replace("\\", "/")),
+      None => write!(f, "<{}>", self.name()),
+    }
+  }
+}
+
+/// A shortcut shared between the provided list and the matches, so matching only copies pointers
+pub type SharedShortcut = std::sync::Arc<Shortcut>;
+
+/// A path from the config with the platform's separators. On Windows a bare drive like `C:` is taken
+/// as its root and `C:dir` as `C:\dir`, and UNC paths like `\\server\share` keep their leading separators
+fn normalize_path(path: &str) -> PathBuf {
+  if !cfg!(windows) {
+    return PathBuf::from(path.replace('\\', "/"));
+  }
+  let path = path.replace('/', "\\");
+  let drive = path.len() >= 2 && path.as_bytes()[0].is_ascii_alphabetic() && path.as_bytes()[1] == b':';
+  if drive && !path[2..].starts_with('\\') {
+    return PathBuf::from(format!("{}\\{}", &path[..2], &path[2..]));
+  }
+  PathBuf::from(path)
+}
+
+/// Replaces the `{placeholder}`s of `text` that `value` has a value for, keeping the others as
+/// they are, like the braces of code in a Snippet
+pub(crate) fn replace_placeholders(text: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+  let mut replaced = String::new();
+  let mut rest = text;
+  while let Some(start) = rest.find('{') {
+    let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+      break;
+    };
+    replaced.push_str(&rest[..start]);
+    match value(&rest[start + 1..end]) {
+      Some(value) => replaced.push_str(&value),
+      None => replaced.push_str(&rest[start..=end]),
+    }
+    rest = &rest[end + 1..];
+  }
+  replaced.push_str(rest);
+  replaced
+}
+
+/// Whether `text` has `{name}` or `{name:raw}`
+fn has_placeholder(text: &str, name: &str) -> bool {
+  text.contains(&format!("{{{name}}}")) || text.contains(&format!("{{{name}:raw}}"))
+}
+
+/// `url` with `{name}` replaced by `value` percent-encoded, and `{name:raw}` by `value` as it is,
+/// for values that are part of the path like `owner/repo`
+pub(crate) fn fill_url(url: &str, name: &str, value: &str) -> String {
+  replace_placeholders(url, |placeholder| match placeholder.strip_prefix(name)? {
+    "" => Some(encode_url_value(value)),
+    ":raw" => Some(value.to_string()),
+    _ => None,
+  })
+}
+
+/// `text` with both `{name}` and `{name:raw}` replaced by `value` as it is, outside of URLs
+fn fill_raw(text: &str, name: &str, value: &str) -> String {
+  replace_placeholders(text, |placeholder| {
+    matches!(placeholder.strip_prefix(name)?, "" | ":raw").then(|| value.to_string())
+  })
+}
+
+/// Percent-encodes a value for a URL, keeping only unreserved characters as they are
+fn encode_url_value(value: &str) -> String {
+  value
+    .bytes()
+    .map(|b| {
+      if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+        (b as char).to_string()
+      } else {
+        format!("%{b:02X}")
+      }
+    })
+    .collect()
+}
+
+/// `normalize_path` with a leading `~` standing for the home dir, as in `~/projects`,
+/// and a leading `./` for the directory the config is in
+fn expand_path(path: &str) -> PathBuf {
+  let relative = path.strip_prefix("./").or(path.strip_prefix(".\\"));
+  if let (Some(rest), Some(dir)) = (relative, ShortcutPathPrefix::Config.dir()) {
+    return dir.join(normalize_path(rest));
+  }
+  let home = ShortcutPathPrefix::Home.dir();
+  match (path.strip_prefix('~'), home) {
+    (Some(""), Some(home)) => home.to_path_buf(),
+    (Some(rest), Some(home)) if rest.starts_with(['/', '\\']) => {
+      home.join(normalize_path(rest.trim_start_matches(['/', '\\'])))
+    }
+    _ => normalize_path(path),
+  }
+}
+
+/// A path as shown in the table, with forward slashes whatever the platform
+pub fn display_path(path: &str) -> String {
+  path.replace('\\', "/")
+}
@@ -0,0 +1,199 @@
+//! `bullet sync`: keeps the config and the files it references in a git repository in its
+//! directory and exchanges it with the config's `sync.remote`, so every machine has the same
+//! shortcuts
+
+use std::{ cell::Cell, path::{ Path, PathBuf } };
+use git2::{
+  build::CheckoutBuilder,
+  Commit,
+  Cred,
+  ErrorCode,
+  FetchOptions,
+  PushOptions,
+  RemoteCallbacks,
+  Repository,
+  RepositoryInitOptions,
+  Signature,
+};
+use tracing::info;
+use crate::{ app::BulletError, config::Config };
+
+/// Commits what changed in the config and its files, merges what the remote has and pushes the result.
+/// Returns what was done, for the user. The working tree is only touched once a merge succeeded,
+/// conflicting changes are left for the user to merge with git
+pub fn run(config: &Config) -> Result<Vec<String>, BulletError> {
+  let dir = Config::dir().ok_or(BulletError::Sync("there is no config directory".to_string()))?;
+  // the repository holds the whole directory, which must not be the home or Documents folder
+  if dir.file_name().is_none_or(|name| name != "bullet") {
+    return Err(
+      BulletError::Sync(
+        format!("{} isn't a bullet directory, move the config into its own bullet/ directory to sync it", dir.display())
+      )
+    );
+  }
+  let files = synced_files(config, &dir);
+  let config = &config.sync;
+  let mut done = vec![];
+  let repo = match Repository::open(&dir) {
+    Ok(repo) => repo,
+    Err(e) if e.code() == ErrorCode::NotFound => {
+      done.push(format!("created a git repository in {}", dir.display()));
+      Repository::init_opts(&dir, RepositoryInitOptions::new().initial_head(&config.branch))?
+    }
+    Err(e) => {
+      return Err(e.into());
+    }
+  };
+  if commit_changes(&repo, &files)? {
+    done.push("committed the changes to the config".to_string());
+  }
+  let Some(url) = &config.remote else {
+    done.push("no sync.remote in the config to exchange it with".to_string());
+    return Ok(done);
+  };
+  if repo.find_remote("origin").is_ok() {
+    repo.remote_set_url("origin", url)?;
+  } else {
+    repo.remote("origin", url)?;
+  }
+  let mut remote = repo.find_remote("origin")?;
+  info!(url, "fetching the config");
+  let mut fetch = FetchOptions::new();
+  fetch.remote_callbacks(callbacks());
+  // the default refspecs, a new remote has no branch to name yet
+  remote.fetch::<&str>(&[], Some(&mut fetch), None)?;
+  if let Ok(fetched) = repo.find_reference(&format!("refs/remotes/origin/{}", config.branch)) {
+    let fetched = repo.reference_to_annotated_commit(&fetched)?;
+    let (analysis, _) = repo.merge_analysis(&[&fetched])?;
+    if analysis.is_fast_forward() || analysis.is_unborn() {
+      let head = repo.find_reference("HEAD")?;
+      let branch = head.symbolic_target().unwrap_or("HEAD").to_string();
+      repo.reference(&branch, fetched.id(), true, "bullet sync: fast-forward")?;
+      repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+      done.push("took the changes of the other machines".to_string());
+    } else if !analysis.is_up_to_date() {
+      let ours = repo.head()?.peel_to_commit()?;
+      let theirs = repo.find_commit(fetched.id())?;
+      let mut merged = repo.merge_commits(&ours, &theirs, None)?;
+      if merged.has_conflicts() {
+        let files: Vec<String> = merged
+          .conflicts()?
+          .filter_map(Result::ok)
+          .filter_map(|conflict| conflict.our.or(conflict.their))
+          .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+          .collect();
+        return Err(
+          BulletError::Sync(
+            format!(
+              "{} changed here and on another machine in the same places, \
+               merge origin/{} in {} with git and sync again",
+              files.join(", "),
+              config.branch,
+              dir.display()
+            )
+          )
+        );
+      }
+      let tree = repo.find_tree(merged.write_tree_to(&repo)?)?;
+      let signature = signature(&repo)?;
+      let message = "Merge the config of another machine";
+      repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[&ours, &theirs])?;
+      repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+      done.push("merged the changes of the other machines".to_string());
+    }
+  }
+  let Ok(head) = repo.head() else {
+    return Ok(done);
+  };
+  let refspec = format!("{}:refs/heads/{}", head.name().unwrap_or("HEAD"), config.branch);
+  let rejected = std::rc::Rc::new(Cell::new(None::<String>));
+  let mut callbacks = callbacks();
+  let rejection = rejected.clone();
+  callbacks.push_update_reference(move |_, status| {
+    rejection.set(status.map(String::from));
+    Ok(())
+  });
+  let mut push = PushOptions::new();
+  push.remote_callbacks(callbacks);
+  info!(url, "pushing the config");
+  remote.push(&[refspec], Some(&mut push))?;
+  if let Some(reason) = rejected.take() {
+    return Err(BulletError::Sync(format!("the remote refused the config: {reason}")));
+  }
+  done.push(format!("pushed to {url}"));
+  Ok(done)
+}
+
+impl From<git2::Error> for BulletError {
+  fn from(e: git2::Error) -> Self {
+    BulletError::Sync(e.message().to_string())
+  }
+}
+
+/// The config file and the files of its shortcuts in `dir`, relative to it
+fn synced_files(config: &Config, dir: &Path) -> Vec<PathBuf> {
+  let config_path = Config::path().ok().and_then(|path| std::path::absolute(path).ok());
+  let mut files: Vec<PathBuf> = config_path
+    .into_iter()
+    .chain(config.shortcuts.iter().filter_map(|shortcut| std::path::absolute(shortcut.get_prefixed_path()).ok()))
+    .filter_map(|path| path.strip_prefix(dir).ok().map(Path::to_path_buf))
+    .filter(|path| !path.as_os_str().is_empty() && !path.starts_with(".git"))
+    .collect();
+  files.sort();
+  files.dedup();
+  files
+}
+
+/// Commits the changes to `files`, returns whether there was any
+fn commit_changes(repo: &Repository, files: &[PathBuf]) -> Result<bool, git2::Error> {
+  let dir = repo.workdir().ok_or(git2::Error::from_str("the repository has no working tree"))?;
+  let mut index = repo.index()?;
+  for file in files {
+    if dir.join(file).is_file() {
+      index.add_path(file)?;
+    } else if index.get_path(file, 0).is_some() {
+      index.remove_path(file)?;
+    }
+  }
+  index.write()?;
+  let tree = repo.find_tree(index.write_tree()?)?;
+  let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+  if head.as_ref().is_some_and(|head| head.tree_id() == tree.id()) {
+    return Ok(false);
+  }
+  let host = std::env::var("COMPUTERNAME").or(std::env::var("HOSTNAME"));
+  let message = match host {
+    Ok(host) => format!("Update the config on {host}"),
+    Err(_) => "Update the config".to_string(),
+  };
+  let signature = signature(repo)?;
+  let parents: Vec<&Commit> = head.iter().collect();
+  repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)?;
+  Ok(true)
+}
+
+/// The user's git identity, or bullet's when git has none
+fn signature(repo: &Repository) -> Result<Signature<'static>, git2::Error> {
+  repo.signature().or_else(|_| Signature::now("bullet", "bullet@localhost"))
+}
+
+/// Authenticates with the ssh agent or git's credential helpers, giving up after a few tries
+/// since libgit2 asks again for as long as they are refused
+fn callbacks<'a>() -> RemoteCallbacks<'a> {
+  let mut callbacks = RemoteCallbacks::new();
+  let attempts = Cell::new(0);
+  callbacks.credentials(move |url, username, allowed| {
+    attempts.set(attempts.get() + 1);
+    if attempts.get() > 3 {
+      return Err(git2::Error::from_str("authentication failed"));
+    }
+    if allowed.is_ssh_key() {
+      return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+    }
+    if allowed.is_user_pass_plaintext() {
+      return Cred::credential_helper(&git2::Config::open_default()?, url, username);
+    }
+    Cred::default()
+  });
+  callbacks
+}
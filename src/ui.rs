@@ -0,0 +1,977 @@
+//! The terminal ui: the results table and the dmenu style picker
+
+use std::{
+  ops::Range,
+  sync::mpsc::{ channel, RecvTimeoutError, Sender },
+  time::{ Duration, Instant },
+};
+use crossterm::{
+  event::{
+    self,
+    DisableMouseCapture,
+    EnableMouseCapture,
+    KeyCode,
+    KeyModifiers,
+    MouseButton,
+    MouseEvent,
+    MouseEventKind,
+  },
+  execute,
+  terminal::{ self, EnterAlternateScreen, LeaveAlternateScreen },
+};
+use ratatui::{
+  layout::{ Constraint, Flex, Layout, Rect },
+  style::{ Color, Style, Stylize },
+  text::{ Line, Span },
+  backend::CrosstermBackend,
+  widgets::{
+    Block,
+    BorderType,
+    Cell,
+    Clear,
+    List,
+    ListState,
+    Padding,
+    Paragraph,
+    Row,
+    Table,
+    TableState,
+  },
+  DefaultTerminal,
+  Frame,
+  Terminal,
+};
+use tracing::{ error, info };
+use tui_textarea::TextArea;
+use crate::{
+  app::{ Action, App, BulletError, Confirmation, Entry, LaunchLog, Message },
+  instance::Summon,
+  replay::ReplayStep,
+  search::{ fold, folded_contains },
+  shortcut::{ display_path, Shortcut, ShortcutKind },
+};
+
+/// Terminals this wide or narrower get the compact table: the aliases with one character glyphs
+pub const COMPACT_WIDTH: u16 = 60;
+/// Terminals narrower than this leave out the last used column
+pub const FULL_WIDTH: u16 = 80;
+
+/// Width the glyph of a shortcut takes with its space, its icon padded to the width of the glyphs of
+/// the kinds so the aliases line up
+fn glyph_width(s: &Shortcut, compact: bool) -> usize {
+  let glyph = if compact { 2 } else { 4 };
+  s.icon.as_ref().map_or(glyph, |icon| (Span::from(icon.as_str()).width() + 1).max(glyph))
+}
+
+/// The glyph of a kind in one cell, `[_] ` turning into `_ `
+fn short_glyph(glyph: Span<'static>) -> Span<'static> {
+  let symbol = glyph.content.chars().find(|c| !"[](){} ".contains(*c)).unwrap_or(' ');
+  Span::styled(format!("{symbol} "), glyph.style)
+}
+
+/// Cell of the aliases of a shortcut after its icon or the glyph of its kind. The alias `search`
+/// matched, or the first one, is in `style`, the others are dimmed, and a launch count `badge` last.
+/// The `color` of the shortcut wins over the colour of the kind
+fn seq_cell(
+  glyph: Span<'static>,
+  style: Style,
+  s: &Shortcut,
+  search: &str,
+  badge: &Option<String>,
+  compact: bool
+) -> Cell<'static> {
+  let search = fold(search);
+  let matched = s.seq.iter().position(|seq| folded_contains(seq, &search)).unwrap_or(0);
+  let (glyph, style) = match s.color.as_deref().and_then(|color| color.parse::<Color>().ok()) {
+    Some(color) => (glyph.fg(color), style.fg(color)),
+    None => (glyph, style),
+  };
+  let glyph = match &s.icon {
+    Some(icon) => {
+      let padding = glyph_width(s, compact) - Span::from(icon.as_str()).width();
+      Span::styled(format!("{icon}{}", " ".repeat(padding)), glyph.style)
+    }
+    None if compact => short_glyph(glyph),
+    None => glyph,
+  };
+  let mut spans = vec![glyph];
+  for (i, seq) in s.seq.iter().enumerate() {
+    if i > 0 {
+      spans.push(Span::from(", ").dim());
+    }
+    spans.push(
+      if i == matched {
+        Span::styled(seq.clone(), style)
+      } else {
+        Span::styled(seq.clone(), style.not_bold().dim())
+      }
+    );
+  }
+  if let Some(badge) = badge {
+    spans.push(Span::from(badge.clone()).dark_gray());
+  }
+  Cell::new(Line::from(spans))
+}
+
+/// Path of a Dir or File with its prefix underlined, after the description when it has one
+fn path_line(s: &Shortcut) -> Line<'static> {
+  let prefix = s.path_prefix.map(|p| p.to_string());
+  let path = match prefix {
+    Some(_) => display_path(s.path.trim_start_matches(['/', '\\'])),
+    None => display_path(&s.path),
+  };
+  let mut spans = vec![];
+  if let Some(description) = &s.description {
+    spans.push(Span::from(description.clone()));
+    spans.push(Span::from("  "));
+  }
+  let style = if s.description.is_some() { Style::new().dark_gray() } else { Style::new() };
+  if let Some(p) = prefix {
+    spans.push(Span::styled(p, style.underlined()));
+    spans.push(Span::styled("/", style.underlined()));
+  }
+  spans.push(Span::styled(path, style));
+  Line::from(spans)
+}
+
+/// Row of the results table for a shortcut, its glyph and colour telling the kind, with a star
+/// when it is a favorite
+fn shortcut_row(
+  s: &Shortcut,
+  search: &str,
+  starred: bool,
+  last_used: Option<String>,
+  badge: Option<String>,
+  compact: bool
+) -> Row<'static> {
+  let mut cells = match s.kind {
+    ShortcutKind::App => {
+      let desc = &s.description.clone().unwrap_or_default();
+      let cells = vec![
+        seq_cell(Span::from(">__ ").red(), Style::new().bold().light_red(), s, search, &badge, compact),
+        Cell::new(desc.clone())
+      ];
+      cells
+    }
+    ShortcutKind::Dir => {
+      let cells = vec![
+        seq_cell(Span::from("[_] ").green(), Style::new().bold().light_green(), s, search, &badge, compact),
+        Cell::new(path_line(s))
+      ];
+      cells
+    }
+    ShortcutKind::File => {
+      let cells = vec![
+        seq_cell(Span::from("[_] ").yellow(), Style::new().bold().light_yellow(), s, search, &badge, compact),
+        Cell::new(path_line(s))
+      ];
+      cells
+    }
+    ShortcutKind::Url => {
+      let desc = s.description.clone().unwrap_or_default();
+      let cells = vec![
+        seq_cell(Span::from("(#) ").blue(), Style::new().bold().light_blue(), s, search, &badge, compact),
+        Cell::new(desc)
+      ];
+      cells
+    }
+    ShortcutKind::Terminal => {
+      let desc = s.description.clone().unwrap_or_else(||
+        std::iter::once(&s.path).chain(&s.args).cloned().collect::<Vec<_>>().join(" ")
+      );
+      let cells = vec![
+        seq_cell(Span::from("[$] ").cyan(), Style::new().bold().light_cyan(), s, search, &badge, compact),
+        Cell::new(desc)
+      ];
+      cells
+    }
+    ShortcutKind::Window => {
+      let cells = vec![
+        seq_cell(Span::from("[=] ").white(), Style::new().bold(), s, search, &badge, compact),
+        Cell::new(s.description.clone().unwrap_or_default())
+      ];
+      cells
+    }
+    ShortcutKind::Snippet => {
+      let cells = vec![
+        seq_cell(Span::from("[\"] ").light_yellow(), Style::new().bold().yellow(), s, search, &badge, compact),
+        Cell::new(s.description.clone().unwrap_or(s.path.clone()))
+      ];
+      cells
+    }
+    ShortcutKind::Note => {
+      let cells = vec![
+        seq_cell(Span::from("[+] ").light_magenta(), Style::new().bold().magenta(), s, search, &badge, compact),
+        Cell::new(path_line(s))
+      ];
+      cells
+    }
+    ShortcutKind::Script => {
+      let cells = vec![
+        seq_cell(Span::from("{&} ").green(), Style::new().bold().light_green(), s, search, &badge, compact),
+        Cell::new(s.description.clone().unwrap_or_default())
+      ];
+      cells
+    }
+    ShortcutKind::Secret => {
+      let cells = vec![
+        seq_cell(Span::from("[*] ").magenta(), Style::new().bold().light_magenta(), s, search, &badge, compact),
+        Cell::new(s.description.clone().unwrap_or_default())
+      ];
+      cells
+    }
+    ShortcutKind::Process => {
+      let cells = vec![
+        seq_cell(Span::from("[x] ").red(), Style::new().bold().light_red(), s, search, &badge, compact),
+        Cell::new(s.description.clone().unwrap_or(s.path.clone()))
+      ];
+      cells
+    }
+    ShortcutKind::Session => {
+      let desc = s.description.clone().unwrap_or_else(|| format!("{} items", s.items.len()));
+      let cells = vec![
+        seq_cell(Span::from("[>] ").light_blue(), Style::new().bold().blue(), s, search, &badge, compact),
+        Cell::new(desc)
+      ];
+      cells
+    }
+    ShortcutKind::Ssh => {
+      let desc = s.description.clone().unwrap_or(s.path.clone());
+      let cells = vec![
+        seq_cell(Span::from("(@) ").magenta(), Style::new().bold().light_magenta(), s, search, &badge, compact),
+        Cell::new(desc)
+      ];
+      cells
+    }
+  };
+  // empty while the column is hidden, the marks would land in it otherwise
+  cells.push(Cell::new(Span::from(last_used.unwrap_or_default()).dim()));
+  if !s.missing {
+    if starred {
+      cells.push(Cell::new(Span::from("★").yellow()));
+    }
+    return Row::new(cells);
+  }
+  cells.push(Cell::new(Span::from("!").bold().yellow()));
+  Row::new(cells).style(Style::new().dim().crossed_out())
+}
+
+/// Launches from which a shortcut gets a launch count badge, fewer don't tell it apart
+const BADGE_LAUNCHES: i64 = 10;
+
+/// Badge of how many times the shortcut was opened, like ` ×41`, for shortcuts opened often
+fn launch_badge(app: &App, s: &Shortcut) -> Option<String> {
+  let launches = *app.launch_counts.as_ref()?.get(s.seq.first()?)?;
+  (launches >= BADGE_LAUNCHES).then(|| format!(" ×{launches}"))
+}
+
+/// How long ago a timestamp was, like `2h ago`, or `never` without one
+fn relative_time(timestamp: Option<i64>) -> String {
+  let Some(timestamp) = timestamp else {
+    return "never".to_string();
+  };
+  let seconds = (chrono::Utc::now().timestamp() - timestamp).max(0);
+  match seconds {
+    0..60 => "just now".to_string(),
+    60..3600 => format!("{}m ago", seconds / 60),
+    3600..86400 => format!("{}h ago", seconds / 3600),
+    86400..2592000 => format!("{}d ago", seconds / 86400),
+    2592000..31536000 => format!("{}mo ago", seconds / 2592000),
+    _ => format!("{}y ago", seconds / 31536000),
+  }
+}
+
+/// The last launches over the matches, one per line: when, what, from which query and whether it
+/// failed
+fn launch_log(frame: &mut Frame, area: Rect, log: &LaunchLog) {
+  let lines: Vec<Line> = if log.launches.is_empty() {
+    vec![Line::from("Nothing was launched yet").dark_gray()]
+  } else {
+    log.launches
+      .iter()
+      .skip(log.offset)
+      .map(|launch| {
+        let outcome = match &launch.error {
+          Some(e) => Span::from(format!("failed: {e}")).red(),
+          None => Span::from("ok").green(),
+        };
+        Line::from(
+          vec![
+            Span::from(format!("{:<9}", relative_time(Some(launch.launched_at)))).dark_gray(),
+            Span::from(launch.seq.clone()).bold(),
+            Span::from(format!("  \"{}\"  ", launch.query)).dark_gray(),
+            outcome
+          ]
+        )
+      })
+      .collect()
+  };
+  let log = Paragraph::new(lines).block(
+    Block::bordered()
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().dark_gray())
+      .title(format!("Last {} launches", log.launches.len()))
+      .title_bottom(Line::from("↑/↓ scroll  esc").dark_gray())
+      .padding(Padding::horizontal(1))
+  );
+  frame.render_widget(Clear, area);
+  frame.render_widget(log, area);
+}
+
+/// Heading row of a category, with an arrow telling whether it is collapsed
+fn category_row(name: &str, len: usize, collapsed: bool) -> Row<'static> {
+  let arrow = if collapsed { "▸ " } else { "▾ " };
+  Row::new(
+    vec![
+      Cell::new(Line::from(vec![Span::from(arrow).dark_gray(), Span::from(name.to_string()).bold()])),
+      Cell::new(Span::from(len.to_string()).dark_gray())
+    ]
+  )
+}
+
+/// Makes panics call `restore` before they are printed, so the message isn't lost on the
+/// alternate screen and the terminal isn't left in raw mode
+fn restore_terminal_on_panic(restore: fn()) {
+  let previous = std::panic::take_hook();
+  std::panic::set_hook(
+    Box::new(move |info| {
+      restore();
+      error!("panicked: {info}");
+      previous(info);
+    })
+  );
+}
+
+/// Calls `restore` and exits when bullet is sent SIGTERM, as hotkey daemons do to close it,
+/// since the signal would otherwise leave the terminal in raw mode
+#[cfg(unix)]
+fn restore_terminal_on_sigterm(restore: fn()) {
+  let Ok(mut signals) = signal_hook::iterator::Signals::new([signal_hook::consts::SIGTERM]) else {
+    error!("failed to listen for SIGTERM");
+    return;
+  };
+  std::thread::spawn(move || {
+    if signals.forever().next().is_some() {
+      info!("terminated");
+      restore();
+      std::process::exit(143);
+    }
+  });
+}
+
+/// Text of a panic payload caught with `catch_unwind`
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+  payload
+    .downcast_ref::<&str>()
+    .map(|message| message.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+    .unwrap_or("unknown panic".to_string())
+}
+
+/// Gives the terminal back as it was before the tui, mouse reporting off
+fn restore_tui() {
+  if let Err(e) = execute!(std::io::stdout(), DisableMouseCapture) {
+    error!("failed to turn mouse reporting off: {e}");
+  }
+  ratatui::restore();
+}
+
+pub fn run_tui(mut app: App) -> Result<(), BulletError> {
+  let mut term = ratatui::init();
+  // clicks select the tabs
+  if let Err(e) = execute!(std::io::stdout(), EnableMouseCapture) {
+    error!("failed to turn mouse reporting on, clicks won't select the tabs: {e}");
+  }
+  restore_terminal_on_panic(restore_tui);
+  #[cfg(unix)]
+  restore_terminal_on_sigterm(restore_tui);
+  let ui = std::panic::catch_unwind(
+    std::panic::AssertUnwindSafe(|| tui_loop(&mut term, &mut app))
+  );
+  restore_tui();
+  let cancelled = ui.map_err(|payload| BulletError::Crashed(panic_message(payload.as_ref())))?;
+  if !cancelled {
+    return Ok(());
+  }
+  match app.config {
+    Err(e) => Err(BulletError::Config(e)),
+    Ok(_) => Err(BulletError::Cancelled),
+  }
+}
+
+/// What the picker waits for
+enum Wake {
+  Terminal(event::Event),
+  /// Another bullet summoned this one
+  Summoned(Summon),
+}
+
+/// Reads the terminal's events on a thread of its own, so they come in on the same channel as the
+/// summons of other bullets
+fn read_terminal(sender: Sender<Wake>) {
+  std::thread::spawn(move || loop {
+    match event::read() {
+      Ok(event) => {
+        if sender.send(Wake::Terminal(event)).is_err() {
+          return;
+        }
+      }
+      Err(e) => {
+        error!("failed to read from the terminal: {e}");
+        return;
+      }
+    }
+  });
+}
+
+/// Runs the ui until a shortcut is opened or the user leaves, returning whether they cancelled
+fn tui_loop(term: &mut DefaultTerminal, app: &mut App) -> bool {
+  let (sender, wakes) = channel();
+  if let Some(instance) = app.instance.as_mut() {
+    instance.forward(sender.clone(), Wake::Summoned);
+  }
+  read_terminal(sender);
+  while app.running {
+    // an idle picker sits here blocked without drawing
+    if app.dirty {
+      if let Err(e) = term.draw(|frame| view(frame, app)) {
+        error!("failed to draw the ui: {e}");
+      }
+      app.dirty = false;
+    }
+    if let Some(step) = app.replay.as_mut().and_then(Iterator::next) {
+      match step {
+        ReplayStep::Key(key_event) => app.update(app.message(key_event)),
+        ReplayStep::Wait(duration) => replay_wait(term, app, duration),
+      }
+      continue;
+    }
+    // blocks until there is input or a summon, only ticking while results are coming in the
+    // background or a toast is to be hidden
+    let loading = app.loading() || app.providers.iter().any(|p| p.pending());
+    let wake = if loading || app.toast.is_some() {
+      let tick = Duration::from_millis(if loading { 30 } else { 200 });
+      match wakes.recv_timeout(tick) {
+        Ok(wake) => {
+          app.receive();
+          wake
+        }
+        Err(RecvTimeoutError::Timeout) => {
+          app.update(Message::Tick);
+          continue;
+        }
+        Err(RecvTimeoutError::Disconnected) => break,
+      }
+    } else {
+      match wakes.recv() {
+        Ok(wake) => wake,
+        Err(_) => break,
+      }
+    };
+    let event = match wake {
+      Wake::Terminal(event) => event,
+      Wake::Summoned(summon) => {
+        app.update(Message::Summoned(summon));
+        continue;
+      }
+    };
+    match event {
+      event::Event::Key(key_event) => app.update(app.message(key_event)),
+      event::Event::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, .. }) => {
+        if let Some(tab) = tab_at(app, column).filter(|_| row == TABS_ROW) {
+          app.update(Message::SelectTab(tab));
+        }
+      }
+      // the next draw lays everything out for the new size, keeping the selection in view
+      event::Event::Resize(..) => {
+        if let Err(e) = term.autoresize() {
+          error!("failed to resize the ui: {e}");
+        }
+        app.dirty = true;
+      }
+      _ => {}
+    }
+  }
+  app.cancelled
+}
+
+/// Waits in a replay, ticking and drawing meanwhile like the picker does while waiting for keys
+fn replay_wait(term: &mut DefaultTerminal, app: &mut App, duration: Duration) {
+  let until = Instant::now() + duration;
+  while app.running && Instant::now() < until {
+    if app.dirty {
+      if let Err(e) = term.draw(|frame| view(frame, app)) {
+        error!("failed to draw the ui: {e}");
+      }
+      app.dirty = false;
+    }
+    std::thread::sleep(until.saturating_duration_since(Instant::now()).min(Duration::from_millis(30)));
+    app.update(Message::Tick);
+  }
+}
+
+/// Rows past the bottom of the results that are built along with the visible ones
+const OVERSCAN: usize = 8;
+
+/// Entries of the app shown in a results area `height` rows high, scrolled from the app's offset
+/// only as far as it takes for the selection to be in view, with `OVERSCAN` more
+pub fn visible_entries(app: &App, height: u16) -> Range<usize> {
+  let height = (height as usize).max(1);
+  let mut start = app.offset.min(app.selected);
+  if app.selected >= start + height {
+    start = app.selected + 1 - height;
+  }
+  // no empty rows at the bottom once the matches got fewer
+  let start = start.min(app.entries.len().saturating_sub(height));
+  let end = (start + height + OVERSCAN).min(app.entries.len());
+  start.min(end)..end
+}
+
+/// Width of the aliases column, as wide as the longest of all the matches so it keeps its width
+/// while scrolling, past which the descriptions would be squeezed. Compact tables don't use it
+pub(crate) fn aliases_width(app: &App) -> u16 {
+  app.entries
+    .iter()
+    .map(|entry| match entry {
+      Entry::Shortcut(s) => {
+        let seqs: usize = s.seq.iter().map(|seq| Span::from(seq.as_str()).width()).sum();
+        let badge = launch_badge(app, s).map_or(0, |badge| Span::from(badge).width());
+        (glyph_width(s, false) + seqs + 2 * s.seq.len().saturating_sub(1) + badge) as u16
+      }
+      Entry::Category { name, .. } => 2 + Span::from(name.as_str()).width() as u16,
+    })
+    .max()
+    .unwrap_or(0)
+    .clamp(8, 32)
+}
+
+/// Table of the `visible` entries of the app for a terminal `width` columns wide, only their rows
+/// are built however many matches there are. Narrow terminals leave out the last used column,
+/// then the descriptions and the long glyphs
+pub fn results_table(app: &App, width: u16, visible: Range<usize>) -> Table<'static> {
+  let search = app.scope_index(&app.search).1;
+  let compact = width <= COMPACT_WIDTH;
+  let last_used = app.last_used.as_ref().filter(|_| width >= FULL_WIDTH);
+  let rows: Vec<Row> = app.entries[visible]
+    .iter()
+    .map(|entry| match entry {
+      Entry::Shortcut(s) => {
+        let last_used = last_used.map(|used| relative_time(s.seq.first().and_then(|seq| used.get(seq)).copied()));
+        shortcut_row(s, search, app.favorites.contains(s), last_used, launch_badge(app, s), compact)
+      }
+      Entry::Category { name, len, collapsed } => category_row(name, *len, *collapsed),
+    })
+    .collect();
+  // the column of the missing path and favorite marks only takes space while there are some
+  let marks = if app.marked { 1 } else { 0 };
+  let last_used = if last_used.is_some() { 8 } else { 0 };
+  // compact tables give the whole width to the aliases
+  let (aliases, descriptions) = if compact {
+    (Constraint::Fill(1), Constraint::Length(0))
+  } else {
+    (Constraint::Length(app.aliases_width), Constraint::Fill(1))
+  };
+  Table::new(rows, vec![aliases, descriptions, Constraint::Length(last_used), Constraint::Length(marks)])
+    .column_spacing(1)
+    .row_highlight_style(Style::new().on_dark_gray())
+}
+
+/// Name of the tab of a kind's matches
+fn tab_name(kind: Option<ShortcutKind>) -> &'static str {
+  match kind {
+    None => "All",
+    Some(ShortcutKind::App) => "Apps",
+    Some(ShortcutKind::Dir) => "Dirs",
+    Some(ShortcutKind::File) => "Files",
+    Some(ShortcutKind::Url) => "URLs",
+    Some(ShortcutKind::Ssh) => "SSH",
+    Some(ShortcutKind::Terminal) => "Commands",
+    Some(ShortcutKind::Window) => "Windows",
+    Some(ShortcutKind::Snippet) => "Snippets",
+    Some(ShortcutKind::Process) => "Processes",
+    Some(ShortcutKind::Secret) => "Secrets",
+    Some(ShortcutKind::Script) => "Scripts",
+    Some(ShortcutKind::Note) => "Notes",
+    Some(ShortcutKind::Session) => "Sessions",
+  }
+}
+
+/// The tabs with their labels, like ` Files 2 `, as they are drawn one after the other
+fn tab_labels(app: &App) -> Vec<(Option<ShortcutKind>, String)> {
+  app.tabs()
+    .into_iter()
+    .map(|(kind, count)| (kind, format!(" {} {count} ", tab_name(kind))))
+    .collect()
+}
+
+/// The tabs in the bottom border of the search, the open one highlighted
+fn tab_strip(app: &App) -> Line<'static> {
+  let mut spans = vec![];
+  for (i, (kind, label)) in tab_labels(app).into_iter().enumerate() {
+    if i > 0 {
+      spans.push(Span::from("│"));
+    }
+    spans.push(if kind == app.tab { Span::from(label).reversed() } else { Span::from(label) });
+  }
+  Line::from(spans)
+}
+
+/// Row of the screen the tabs are drawn on
+pub const TABS_ROW: u16 = 2;
+
+/// The tab drawn at a column of `TABS_ROW`, for mouse clicks
+pub fn tab_at(app: &App, column: u16) -> Option<Option<ShortcutKind>> {
+  // the tabs start after the corner of the border
+  let mut start = 1;
+  for (kind, label) in tab_labels(app) {
+    let end = start + Span::from(label).width() as u16;
+    if (start..end).contains(&column) {
+      return Some(kind);
+    }
+    start = end + 1;
+  }
+  None
+}
+
+/// Draws the labels of the first `rows` rows over their glyphs while the rows are labelled, the
+/// ones `typed` rules out are left out
+fn hint_labels(frame: &mut Frame, area: Rect, rows: usize, typed: &str) {
+  for row in 0..rows {
+    let label = App::hint_label(row);
+    let Some(rest) = label.strip_prefix(typed) else {
+      continue;
+    };
+    let label = Line::from(vec![Span::from(typed.to_string()).dim(), Span::from(rest.to_string())])
+      .style(Style::new().bold().black().on_yellow());
+    frame.render_widget(label, Rect::new(area.x, area.y + row as u16, 2, 1));
+  }
+}
+
+/// Smallest terminal the ui is drawn in, the search and a few matches fit
+pub const MIN_SIZE: (u16, u16) = (30, 8);
+
+/// Whether the frame is below `MIN_SIZE`, in which case `too_small` is drawn instead. Returns
+/// whether it was
+fn too_small(frame: &mut Frame) -> bool {
+  let area = frame.area();
+  if area.width >= MIN_SIZE.0 && area.height >= MIN_SIZE.1 {
+    return false;
+  }
+  let note = Paragraph::new(
+    vec![
+      Line::from("Terminal too small"),
+      Line::from(format!("{}x{}, needs {}x{}", area.width, area.height, MIN_SIZE.0, MIN_SIZE.1)).dark_gray()
+    ]
+  ).centered();
+  let [area] = Layout::vertical([Constraint::Length(2)])
+    .flex(Flex::Center)
+    .areas(area);
+  frame.render_widget(note, area);
+  true
+}
+
+/// Draws the app: the search, the matches in view and the popup that is open. Keeps where the
+/// results are scrolled to
+pub fn view(frame: &mut Frame, app: &mut App) {
+  if too_small(frame) {
+    return;
+  }
+  let layout = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]);
+  let [search_area, main_area] = layout.areas(frame.area());
+
+  let visible = visible_entries(app, main_area.height);
+  app.offset = visible.start;
+  app.rows_in_view = main_area.height as usize;
+  let app = &*app;
+  let mut table_state = TableState::default().with_selected(Some(app.selected - visible.start));
+  let table = results_table(app, frame.area().width, visible.clone());
+
+  let mut search_block = Block::bordered()
+    .border_type(BorderType::Rounded)
+    .border_style(Style::new().dark_gray())
+    .padding(Padding::horizontal(1));
+  if app.config.is_ok() {
+    search_block = search_block.title_bottom(tab_strip(app));
+  }
+  let search = app.config.as_ref().map(|config| config.search.clone()).unwrap_or_default();
+  if let Some(title) = search.title {
+    search_block = search_block.title(Span::from(format!(" {title} ")).gray());
+  }
+  let mut input_area = search_block.inner(search_area);
+  if let Some(prompt) = search.prompt {
+    let prompt = Span::from(format!("{prompt} ")).bold().light_blue();
+    let [prompt_area, rest] = Layout::horizontal([Constraint::Length(prompt.width() as u16), Constraint::Fill(1)])
+      .areas(input_area);
+    frame.render_widget(prompt, prompt_area);
+    input_area = rest;
+  }
+  frame.render_widget(&app.search_input, input_area);
+  if let Some(placeholder) = search.placeholder.filter(|_| app.search_input.is_empty()) {
+    // after the cursor, which stays in the first cell
+    let [_, placeholder_area] = Layout::horizontal([Constraint::Length(1), Constraint::Fill(1)]).areas(input_area);
+    frame.render_widget(Span::from(placeholder).dark_gray(), placeholder_area);
+  }
+  frame.render_widget(search_block, search_area);
+  match &app.config {
+    Ok(_) => {
+      frame.render_stateful_widget(&table, main_area, &mut table_state);
+      if let Some(typed) = &app.hints {
+        hint_labels(frame, main_area, app.in_view().len(), typed);
+      }
+      if let Some(pending) = &app.pending_confirmation {
+        let shortcut = pending.shortcut();
+        let (verb, rest) = match pending {
+          Confirmation::Delete(_) => ("Delete ", " from the config? "),
+          Confirmation::Open(s) if s.kind == ShortcutKind::Process => ("Kill ", "? "),
+          Confirmation::Open(_) => ("Open ", "? "),
+        };
+        let question = Paragraph::new(
+          Line::from(
+            vec![
+              Span::from(verb),
+              Span::from(shortcut.description.clone().unwrap_or(shortcut.name().to_string())).bold(),
+              Span::from(rest),
+              Span::from("y/n").dark_gray()
+            ]
+          )
+        ).block(
+          Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().yellow())
+            .padding(Padding::horizontal(1))
+        );
+        let [area] = Layout::vertical([Constraint::Length(3)])
+          .flex(Flex::Center)
+          .areas(main_area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(question, area);
+      }
+      if let Some(prompt) = &app.snippet_prompt {
+        let question = Paragraph::new(
+          Line::from(
+            vec![
+              Span::from(format!("{}: ", prompt.name())),
+              Span::from(prompt.input.clone()).bold(),
+              Span::from("_").slow_blink(),
+              Span::from("  enter/esc").dark_gray()
+            ]
+          )
+        ).block(
+          Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().yellow())
+            .title(prompt.shortcut.description.clone().unwrap_or(prompt.shortcut.name().to_string()))
+            .padding(Padding::horizontal(1))
+        );
+        let [area] = Layout::vertical([Constraint::Length(3)])
+          .flex(Flex::Center)
+          .areas(main_area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(question, area);
+      }
+      if let Some(menu) = &app.action_menu {
+        let shortcut = &menu.shortcut;
+        let lines: Vec<Line> = match &menu.application {
+          Some(application) => vec![
+            Line::from(
+              vec![
+                Span::from("Open with "),
+                Span::from(application.clone()).bold(),
+                Span::from("_").slow_blink(),
+                Span::from("  enter/esc").dark_gray()
+              ]
+            )
+          ],
+          None => menu.actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+              let label = match action {
+                Action::Pin if app.favorites.contains(shortcut) => "unpin",
+                action => action.label(),
+              };
+              let line = Line::from(vec![Span::from(format!("{} ", action.key())).dark_gray(), Span::from(label)]);
+              if i == menu.selected { line.reversed() } else { line }
+            })
+            .collect(),
+        };
+        let height = lines.len() as u16 + 2;
+        let menu = Paragraph::new(lines).block(
+          Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().dark_gray())
+            .title(shortcut.name().to_string())
+            .padding(Padding::horizontal(1))
+        );
+        let [area] = Layout::vertical([Constraint::Length(height)])
+          .flex(Flex::Center)
+          .areas(main_area);
+        let [area] = Layout::horizontal([Constraint::Length(32)])
+          .flex(Flex::Center)
+          .areas(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(menu, area);
+      }
+      if let Some(failed) = &app.failed_open {
+        let name = failed.shortcut.description.clone().unwrap_or(failed.shortcut.name().to_string());
+        let options = match &failed.application {
+          Some(application) =>
+            Line::from(
+              vec![
+                Span::from("Open with "),
+                Span::from(application.clone()).bold(),
+                Span::from("_").slow_blink(),
+                Span::from("  enter/esc").dark_gray()
+              ]
+            ),
+          None => {
+            let mut options = vec![Span::from("r").bold(), Span::from(" retry  ")];
+            if failed.shortcut.kind != ShortcutKind::Url {
+              options.extend([Span::from("f").bold(), Span::from(" open folder  ")]);
+            }
+            options.extend([
+              Span::from("c").bold(),
+              Span::from(" copy path  "),
+              Span::from("a").bold(),
+              Span::from(" open with…  "),
+              Span::from("esc").dark_gray(),
+            ]);
+            Line::from(options)
+          }
+        };
+        let failure = Paragraph::new(
+          vec![
+            Line::from(
+              vec![
+                Span::from("Failed to open "),
+                Span::from(name).bold(),
+                Span::from(": "),
+                Span::from(failed.error.clone()).red()
+              ]
+            ),
+            options
+          ]
+        ).block(
+          Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().red())
+            .padding(Padding::horizontal(1))
+        );
+        let [area] = Layout::vertical([Constraint::Length(4)])
+          .flex(Flex::Center)
+          .areas(main_area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(failure, area);
+      }
+      if let Some(log) = &app.launch_log {
+        launch_log(frame, main_area, log);
+      }
+    }
+    Err(e) => {
+      let error_p = Paragraph::new(e.to_string());
+      frame.render_widget(&error_p, main_area);
+    }
+  }
+  if let Some(toast) = &app.toast {
+    let toast_p = Paragraph::new(toast.text.clone()).block(
+      Block::bordered()
+        .border_type(BorderType::Rounded)
+        .border_style(if toast.failed { Style::new().red() } else { Style::new().green() })
+        .padding(Padding::horizontal(1))
+    );
+    let [area] = Layout::vertical([Constraint::Length(3)])
+      .flex(Flex::End)
+      .areas(main_area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(toast_p, area);
+  }
+}
+
+/// Picker over arbitrary lines. The ui is drawn on stderr since stdout carries the chosen line
+pub fn run_dmenu(candidates: Vec<String>) -> Result<String, BulletError> {
+  let setup = |e| BulletError::Io("set up the terminal".to_string(), e);
+  terminal::enable_raw_mode().map_err(setup)?;
+  execute!(std::io::stderr(), EnterAlternateScreen).map_err(setup)?;
+  restore_terminal_on_panic(restore_stderr_terminal);
+  #[cfg(unix)]
+  restore_terminal_on_sigterm(restore_stderr_terminal);
+  let mut term = Terminal::new(CrosstermBackend::new(std::io::stderr())).unwrap();
+
+  let mut search_input = TextArea::default();
+  search_input.set_block(
+    Block::bordered()
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().dark_gray())
+      .padding(Padding::horizontal(1))
+  );
+  let mut matched: Vec<&String> = candidates.iter().collect();
+  let mut list_state = ListState::default().with_selected(Some(0));
+
+  let chosen = loop {
+    let drawn = term.draw(|frame| {
+      if too_small(frame) {
+        return;
+      }
+      let layout = Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]);
+      let [search_area, main_area] = layout.areas(frame.area());
+      let list = List::new(matched.iter().map(|c| c.as_str()))
+        .highlight_style(Style::new().bold().light_blue())
+        .highlight_symbol("> ");
+      frame.render_widget(&search_input, search_area);
+      frame.render_stateful_widget(list, main_area, &mut list_state);
+    });
+    if let Err(e) = drawn {
+      error!("failed to draw the picker: {e}");
+    }
+    let key_event = match event::read().unwrap() {
+      event::Event::Key(key_event) => key_event,
+      event::Event::Resize(..) => {
+        if let Err(e) = term.autoresize() {
+          error!("failed to resize the picker: {e}");
+        }
+        continue;
+      }
+      _ => continue,
+    };
+    match key_event.code {
+      KeyCode::Esc => {
+        break None;
+      }
+      KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+        break None;
+      }
+      KeyCode::Enter => {
+        // like dmenu, the typed text itself is returned when nothing matches
+        let typed = search_input.lines()[0].clone();
+        break Some(
+          list_state
+            .selected()
+            .and_then(|i| matched.get(i))
+            .map(|c| c.to_string())
+            .unwrap_or(typed)
+        );
+      }
+      KeyCode::Up => list_state.select_previous(),
+      KeyCode::Down => list_state.select_next(),
+      _ => {
+        search_input.input(key_event);
+        let search = fold(&search_input.lines()[0]);
+        matched = candidates
+          .iter()
+          .filter(|c| folded_contains(c, &search))
+          .collect();
+        list_state.select(if matched.is_empty() { None } else { Some(0) });
+      }
+    }
+  };
+
+  restore_stderr_terminal();
+  chosen.ok_or(BulletError::Cancelled)
+}
+
+/// Undoes what `run_dmenu` did to the terminal
+fn restore_stderr_terminal() {
+  if let Err(e) = terminal::disable_raw_mode().and_then(|()| execute!(std::io::stderr(), LeaveAlternateScreen)) {
+    error!("failed to give the terminal back: {e}");
+  }
+}
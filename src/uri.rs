@@ -0,0 +1,131 @@
+//! `bullet://` links, opening the shortcut they name from a browser, a Stream Deck or any other app
+
+use std::{ fs, process::Command };
+use directories::BaseDirs;
+use tracing::info;
+
+/// What a `bullet://` link asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriAction {
+  /// `bullet://open?seq=<seq>`, opens the shortcut like `bullet <seq>`
+  Open(String),
+}
+
+impl UriAction {
+  pub fn parse(uri: &str) -> Result<UriAction, String> {
+    let rest = uri.strip_prefix("bullet://").ok_or(format!("\"{uri}\" is not a bullet:// link"))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let param = |name: &str| {
+      query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+    };
+    match action.trim_end_matches('/') {
+      "open" =>
+        param("seq")
+          .filter(|seq| !seq.is_empty())
+          .map(UriAction::Open)
+          .ok_or(format!("\"{uri}\" has no seq")),
+      action => Err(format!("\"{action}\" is not something a bullet:// link can do")),
+    }
+  }
+}
+
+/// Decodes the `%XX` escapes and `+` spaces of a query parameter
+fn percent_decode(value: &str) -> String {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let escaped = bytes
+      .get(i + 1..i + 3)
+      // from_str_radix would take a sign too, `%+1` is not an escape
+      .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+      .and_then(|hex| std::str::from_utf8(hex).ok())
+      .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+    match (bytes[i], escaped) {
+      (b'%', Some(byte)) => {
+        decoded.push(byte);
+        i += 3;
+        continue;
+      }
+      (b'+', _) => decoded.push(b' '),
+      (byte, _) => decoded.push(byte),
+    }
+    i += 1;
+  }
+  String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Registers this bullet as the handler of `bullet://` links for the current user,
+/// running `bullet uri open <link>` for them
+pub fn install() -> std::io::Result<()> {
+  let exe = std::env::current_exe()?;
+  if cfg!(windows) {
+    let key = r"HKCU\Software\Classes\bullet";
+    let command = format!("\"{}\" uri open \"%1\"", exe.display());
+    for args in [
+      vec!["add", key, "/ve", "/d", "URL:bullet", "/f"],
+      vec!["add", key, "/v", "URL Protocol", "/d", "", "/f"],
+      vec!["add", &format!(r"{key}\shell\open\command"), "/ve", "/d", &command, "/f"],
+    ] {
+      run(Command::new("reg").args(args))?;
+    }
+  } else if cfg!(target_os = "macos") {
+    return Err(
+      std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "macOS only hands links to app bundles, wrap `bullet uri open` in one that declares the bullet scheme"
+      )
+    );
+  } else {
+    let dir = BaseDirs::new()
+      .map(|dirs| dirs.data_dir().join("applications"))
+      .ok_or(std::io::Error::other("there is no data directory for the desktop entry"))?;
+    fs::create_dir_all(&dir)?;
+    let entry = format!(
+      "[Desktop Entry]\nType=Application\nName=bullet\nExec=\"{}\" uri open %u\nNoDisplay=true\nMimeType=x-scheme-handler/bullet;\n",
+      exe.display()
+    );
+    fs::write(dir.join("bullet-uri.desktop"), entry)?;
+    run(Command::new("xdg-mime").args(["default", "bullet-uri.desktop", "x-scheme-handler/bullet"]))?;
+  }
+  info!(exe = %exe.display(), "registered the bullet:// scheme");
+  Ok(())
+}
+
+fn run(command: &mut Command) -> std::io::Result<()> {
+  let program = command.get_program().to_string_lossy().to_string();
+  let status = command.status().map_err(|e| std::io::Error::new(e.kind(), format!("{program}: {e}")))?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(std::io::Error::other(format!("{command:?} failed with {status}")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn percent_decodes_query_parameters() {
+    assert_eq!(percent_decode("git%20hub+repo"), "git hub repo");
+    assert_eq!(percent_decode("caf%C3%A9"), "café");
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    assert_eq!(percent_decode("%+1"), "% 1");
+    assert_eq!(percent_decode("%FF"), "\u{FFFD}");
+  }
+
+  #[test]
+  fn parses_open_links() {
+    assert_eq!(UriAction::parse("bullet://open?seq=gh%2Fme"), Ok(UriAction::Open("gh/me".to_string())));
+    assert_eq!(UriAction::parse("bullet://open/?x=1&seq=notes"), Ok(UriAction::Open("notes".to_string())));
+    assert!(UriAction::parse("bullet://open?seq=").is_err());
+    assert!(UriAction::parse("bullet://close?seq=gh").is_err());
+    assert!(UriAction::parse("https://open?seq=gh").is_err());
+  }
+}
@@ -0,0 +1,1029 @@
+//! Drives the app with keys against a `TestBackend` and checks what it draws, so the ui is tested
+//! without a terminal
+
+use std::{
+  path::{ Path, PathBuf },
+  sync::{ Arc, Mutex },
+  time::{ Duration, Instant },
+};
+use crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+use ratatui::{ backend::TestBackend, style::{ Color, Modifier }, Terminal };
+use bullet_core::{
+  app::{ App, Message },
+  config::{ Config, LoadConfigError },
+  favorites::Favorites,
+  init::{ self, Detected },
+  launcher::{ Launch, Launcher, TerminalEmulator },
+  replay::{ Replay, ReplayStep },
+  shortcut::{ Shortcut, ShortcutKind },
+  ui::{ tab_at, view },
+};
+
+/// Shortcuts none of the keys below open right away
+const CONFIG: &str = r#"{
+  "shortcuts": [
+    { "path": "/nowhere/notes.txt", "seq": ["notes"], "kind": "file" },
+    { "path": "/nowhere/novel.txt", "seq": ["novel"], "kind": "file" },
+    { "path": "https://alpha.example", "seq": ["alpha"], "kind": "url", "description": "Alpha" },
+    { "path": "https://also.example", "seq": ["also"], "kind": "url", "description": "Also" },
+    { "path": "https://beta.example", "seq": ["beta"], "kind": "url", "description": "Beta" },
+    { "path": "https://gamma.example", "seq": ["gamma"], "kind": "url", "description": "Gamma", "confirm": true }
+  ],
+  "providers": { "calculator": false }
+}"#;
+
+/// Records what the app opens instead of opening it, failing every launch when `failing`
+#[derive(Default)]
+struct RecordingLauncher {
+  opened: Mutex<Vec<String>>,
+  failing: bool,
+}
+
+impl RecordingLauncher {
+  fn record(&self, what: String) -> std::io::Result<()> {
+    self.opened.lock().unwrap().push(what);
+    Ok(())
+  }
+  fn opened(&self) -> Vec<String> {
+    self.opened.lock().unwrap().clone()
+  }
+}
+
+impl Launcher for RecordingLauncher {
+  fn launch(&self, shortcut: &Shortcut) -> std::io::Result<()> {
+    if self.failing {
+      return Err(std::io::Error::other("no application"));
+    }
+    match &shortcut.browser {
+      Some(browser) => self.record(format!("launch {} in {}", shortcut.name(), browser.command)),
+      None if shortcut.kind == ShortcutKind::Snippet => {
+        self.record(format!("launch {} with {}", shortcut.name(), shortcut.path))
+      }
+      None => self.record(format!("launch {}", shortcut.name())),
+    }
+  }
+  fn open(&self, path: &Path) -> std::io::Result<()> {
+    self.record(format!("open {}", path.display()))
+  }
+  fn open_with(&self, path: &Path, application: &str) -> std::io::Result<()> {
+    self.record(format!("open {} with {application}", path.display()))
+  }
+  fn reveal(&self, path: &Path) -> std::io::Result<()> {
+    self.record(format!("reveal {}", path.display()))
+  }
+  fn open_terminal(&self, dir: &Path) -> std::io::Result<()> {
+    self.record(format!("terminal {}", dir.display()))
+  }
+  fn edit(&self, path: &Path) -> std::io::Result<()> {
+    self.record(format!("edit {}", path.display()))
+  }
+  fn copy(&self, text: &str) -> std::io::Result<()> {
+    self.record(format!("copy {text}"))
+  }
+  fn records_history(&self) -> bool {
+    false
+  }
+}
+
+/// An app on a test terminal, fed keys the way the tui loop feeds them
+struct Harness {
+  app: App,
+  terminal: Terminal<TestBackend>,
+  launcher: Arc<RecordingLauncher>,
+}
+
+impl Harness {
+  fn new(config: &str) -> Self {
+    Harness::with_config(Ok(serde_json::from_str::<Config>(config).unwrap()))
+  }
+  fn with_config(config: Result<Config, LoadConfigError>) -> Self {
+    Harness::with_launcher(config, RecordingLauncher::default())
+  }
+  fn with_launcher(config: Result<Config, LoadConfigError>, launcher: RecordingLauncher) -> Self {
+    let launcher = Arc::new(launcher);
+    let mut app = App::with_config(config, None)
+      .with_launcher(launcher.clone())
+      .with_favorites(Favorites::default());
+    app.wait_for_shortcuts();
+    Harness { app, terminal: Terminal::new(TestBackend::new(80, 12)).unwrap(), launcher }
+  }
+  fn key(&mut self, key_event: KeyEvent) {
+    let message = self.app.message(key_event);
+    self.app.update(message);
+    self.settle();
+  }
+  fn press(&mut self, code: KeyCode) {
+    self.key(KeyEvent::new(code, KeyModifiers::NONE));
+  }
+  fn type_text(&mut self, text: &str) {
+    for c in text.chars() {
+      self.press(KeyCode::Char(c));
+    }
+  }
+  /// Presses the keys of a replay script, settling at its waits
+  fn replay(&mut self, script: &str) {
+    for step in Replay::parse(script).unwrap() {
+      match step {
+        ReplayStep::Key(key_event) => self.key(key_event),
+        ReplayStep::Wait(_) => self.settle(),
+      }
+    }
+  }
+  /// Ticks until every worker has answered the query
+  fn settle(&mut self) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while self.app.loading() && Instant::now() < deadline {
+      self.app.update(Message::Tick);
+      std::thread::sleep(Duration::from_millis(1));
+    }
+  }
+  /// Draws the app and returns the lines of the screen, clearing its dirty flag as the tui loop does
+  fn draw(&mut self) -> Vec<String> {
+    self.terminal.draw(|frame| view(frame, &mut self.app)).unwrap();
+    self.app.dirty = false;
+    let buffer = self.terminal.backend().buffer();
+    (0..buffer.area.height)
+      .map(|y| {
+        let line: String = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+        line.trim_end().to_string()
+      })
+      .collect()
+  }
+  /// Resizes the test terminal, as the tui loop does on a resize event
+  fn resize(&mut self, width: u16, height: u16) {
+    self.terminal.backend_mut().resize(width, height);
+  }
+  /// Background colour of the first cell of a row of the results, which start below the search
+  fn row_background(&self, row: u16) -> Color {
+    self.terminal.backend().buffer()[(0, 3 + row)].bg
+  }
+}
+
+#[test]
+fn lists_the_matches_of_the_query() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  let screen = harness.draw();
+  assert!(screen[1].contains("al"));
+  assert_eq!(screen[3], "(#) alpha Alpha");
+  assert_eq!(screen[4], "(#) also  Also");
+  assert!(!screen.iter().any(|line| line.contains("Beta")));
+  assert!(harness.app.running);
+}
+
+#[test]
+fn a_color_of_a_shortcut_wins_over_the_color_of_its_kind() {
+  let config = CONFIG.replace(r#""description": "Beta""#, r##""description": "Beta", "color": "#ff0000""##);
+  let mut harness = Harness::new(&config);
+  harness.type_text("a");
+  let screen = harness.draw();
+  let row = screen.iter().position(|line| line.contains("beta")).unwrap() as u16;
+  let buffer = harness.terminal.backend().buffer();
+  assert_eq!(buffer[(0, row)].fg, Color::Rgb(255, 0, 0));
+  assert_eq!(buffer[(4, row)].fg, Color::Rgb(255, 0, 0));
+  assert_eq!(buffer[(4, row - 1)].fg, Color::LightBlue);
+}
+
+#[test]
+fn the_config_sets_the_prompt_placeholder_and_title_of_the_search() {
+  let config = CONFIG.replace(
+    r#""providers""#,
+    r#""search": { "prompt": ">", "placeholder": "Search shortcuts", "title": "work" }, "providers""#
+  );
+  let mut harness = Harness::new(&config);
+  let screen = harness.draw();
+  assert!(screen[0].contains(" work "));
+  assert!(screen[1].starts_with("│ >  Search shortcuts"));
+  harness.type_text("al");
+  let screen = harness.draw();
+  assert!(screen[1].starts_with("│ > al"));
+  assert!(!screen[1].contains("Search shortcuts"));
+}
+
+#[test]
+fn backspace_widens_the_matches_again() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("als");
+  assert_eq!(harness.draw().iter().filter(|line| line.contains("(#)")).count(), 1);
+  harness.press(KeyCode::Backspace);
+  assert_eq!(harness.draw().iter().filter(|line| line.contains("(#)")).count(), 2);
+}
+
+#[test]
+fn lists_every_alias_with_the_matched_one_highlighted() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://mail.example", "seq": ["mail", "inbox"], "kind": "url" },
+      { "path": "https://intranet.example", "seq": ["intranet"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  assert_eq!(harness.draw()[3], "(#) mail, inbox");
+  harness.type_text("n");
+  harness.draw();
+  let buffer = harness.terminal.backend().buffer();
+  assert!(buffer[(4, 3)].modifier.contains(Modifier::DIM));
+  assert!(buffer[(10, 3)].modifier.contains(Modifier::BOLD));
+}
+
+#[test]
+fn finds_and_shows_files_by_their_description() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "/nowhere/taxes.ods", "seq": ["tx"], "kind": "file", "description": "Taxes 2026" },
+      { "path": "/nowhere/budget.ods", "seq": ["bg"], "kind": "file", "description": "Budget 2026" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("2026");
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("[_] tx   Taxes 2026  /nowhere/taxes.ods"));
+  assert!(screen[4].starts_with("[_] bg   Budget 2026  /nowhere/budget.ods"));
+}
+
+#[test]
+fn finds_files_by_the_folders_of_their_path_after_closer_matches() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "/nowhere/finance/2024/invoices", "seq": ["fin"], "kind": "dir" },
+      { "path": "/nowhere/work/sent", "seq": ["sent"], "kind": "dir", "description": "Invoices sent" },
+      { "path": "https://invoices.example", "seq": ["bill"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("invoice");
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("[_] sent Invoices sent"));
+  assert!(screen[4].starts_with("[_] fin  /nowhere/finance"));
+  assert!(!screen.iter().any(|line| line.contains("bill")));
+}
+
+#[test]
+fn lists_categories_that_collapse_while_the_query_is_empty() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://mail.example", "seq": ["mail"], "kind": "url", "category": "Work" },
+      { "path": "https://wiki.example", "seq": ["wiki"], "kind": "url", "category": "Work" },
+      { "path": "https://news.example", "seq": ["news"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  let screen = harness.draw();
+  assert_eq!(screen[3..8], ["▾ Work   2", "(#) mail", "(#) wiki", "▾ Other  1", "(#) news"]);
+  harness.press(KeyCode::Down);
+  harness.press(KeyCode::Left);
+  assert_eq!(harness.draw()[3..6], ["▸ Work   2", "▾ Other  1", "(#) news"]);
+  assert_eq!(harness.app.selected, 0);
+  harness.press(KeyCode::Right);
+  assert_eq!(harness.draw()[4], "(#) mail");
+  // Enter on a heading toggles it as well
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.draw()[4], "▾ Other  1");
+  assert!(harness.launcher.opened().is_empty());
+
+  harness.type_text("w");
+  assert_eq!(harness.draw()[3], "(#) wiki");
+}
+
+#[test]
+fn shows_the_icon_of_a_shortcut_instead_of_the_glyph_of_its_kind() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://books.example", "seq": ["books"], "kind": "url", "icon": "📘" },
+      { "path": "https://bank.example", "seq": ["bank"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  let screen = harness.draw();
+  // the emoji takes two cells, the one it covers reads as a space, and is padded to line up
+  assert_eq!(screen[3], "📘   books");
+  assert_eq!(screen[4], "(#) bank");
+}
+
+#[test]
+fn shows_when_shortcuts_were_last_used_once_the_column_is_on() {
+  let config = r#"{
+    "shortcuts": [{ "path": "https://unused.example", "seq": ["unopened-test"], "kind": "url" }],
+    "columns": { "last_used": true },
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  assert!(harness.draw()[3].ends_with("never"));
+  assert!(!Harness::new(CONFIG).draw()[3].ends_with("never"));
+}
+
+#[test]
+fn shows_how_often_the_shortcuts_opened_often_were_opened() {
+  let mut harness = Harness::new(CONFIG);
+  harness.app.launch_counts = Some([("alpha".to_string(), 41), ("also".to_string(), 3)].into());
+  harness.type_text("al");
+  let screen = harness.draw();
+  assert_eq!(screen[3], "(#) alpha ×41 Alpha");
+  assert_eq!(screen[4], "(#) also      Also");
+}
+
+#[test]
+fn lists_the_recently_launched_first_while_the_query_is_empty() {
+  let mut harness = Harness::new(CONFIG);
+  harness.app.recent = vec!["beta".to_string(), "gone".to_string(), "alpha".to_string()];
+  harness.app.update_matches("");
+  let screen = harness.draw();
+  assert_eq!(screen[3], "▾ Recently launched 2");
+  assert!(screen[4].starts_with("(#) beta"));
+  assert!(screen[5].starts_with("(#) alpha"));
+  assert_eq!(screen[6], "▾ Other             6");
+  harness.type_text("al");
+  assert_eq!(harness.draw()[3], "(#) alpha Alpha");
+}
+
+#[test]
+fn orders_the_matches_by_when_they_were_last_used() {
+  let config = CONFIG.replace(r#""providers""#, r#""order": "last_used", "providers""#);
+  let mut harness = Harness::new(&config);
+  harness.app.last_used = Some([("beta".to_string(), 200), ("novel".to_string(), 300)].into());
+  harness.app.update_matches("");
+  let seqs: Vec<&str> = harness.app.matched_shortcuts.iter().map(|s| s.seq[0].as_str()).collect();
+  assert_eq!(seqs, ["novel", "beta", "notes", "alpha", "also", "gamma"]);
+}
+
+#[test]
+fn a_shortcut_without_a_seq_is_listed_and_opened_without_panicking() {
+  let config = CONFIG.replace(
+    r#""shortcuts": ["#,
+    r#""shortcuts": [{ "path": "https://noseq.example", "seq": [], "kind": "url", "description": "No seq" },"#
+  );
+  let mut harness = Harness::new(&config);
+  let screen = harness.draw();
+  let row = screen.iter().position(|line| line.contains("No seq")).unwrap();
+  for _ in 3..row {
+    harness.press(KeyCode::Down);
+  }
+  harness.key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+  assert!(harness.draw().iter().any(|line| line.contains("without a seq can't be starred")));
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["launch https://noseq.example"]);
+}
+
+#[test]
+fn ctrl_f_stars_a_match_into_the_favorites() {
+  let path = std::env::temp_dir().join(format!("bullet-favorites-{}.json", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+  let mut harness = Harness::new(CONFIG);
+  harness.app = harness.app.with_favorites(Favorites::at(path.clone()));
+  harness.type_text("al");
+  harness.press(KeyCode::Down);
+  harness.key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+  // the starred match ranks first and stays highlighted
+  let screen = harness.draw();
+  assert_eq!(screen[3], format!("{:<79}★", "(#) also  Also"));
+  assert_eq!(harness.app.selected, 0);
+  assert!(screen.iter().any(|line| line.contains("Starred also")));
+  assert_eq!(std::fs::read_to_string(&path).unwrap().split_whitespace().collect::<String>(), r#"["also"]"#);
+
+  let mut harness = Harness::new(CONFIG);
+  harness.app = harness.app.with_favorites(Favorites::at(path.clone()));
+  let screen = harness.draw();
+  assert_eq!(screen[3], "▾ Favorites 1");
+  assert!(screen[4].starts_with("(#) also"));
+  assert_eq!(screen[5], "▾ Other     5");
+  harness.press(KeyCode::Down);
+  harness.key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+  assert!(harness.draw()[3].starts_with("[_] notes"));
+  std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn narrow_terminals_drop_the_descriptions_and_the_last_used_column() {
+  let config = r#"{
+    "shortcuts": [{ "path": "https://unused.example", "seq": ["unopened-test"], "kind": "url", "description": "Unused" }],
+    "columns": { "last_used": true },
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  assert!(harness.draw()[3].ends_with("never"));
+  harness.resize(70, 12);
+  assert_eq!(harness.draw()[3], "(#) unopened-test Unused");
+  harness.resize(60, 12);
+  assert_eq!(harness.draw()[3], "# unopened-test");
+}
+
+#[test]
+fn shows_a_placeholder_while_the_terminal_is_too_small() {
+  let mut harness = Harness::new(CONFIG);
+  for _ in 0..5 {
+    harness.press(KeyCode::Down);
+  }
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  harness.resize(20, 5);
+  let screen = harness.draw();
+  assert!(screen.iter().any(|line| line.contains("Terminal too small")));
+  assert!(screen.iter().any(|line| line.contains("20x5, needs 30x8")));
+
+  harness.press(KeyCode::Esc);
+  harness.resize(40, 8);
+  assert_eq!(harness.app.selected_shortcut().unwrap().seq[0], "gamma");
+  assert!(harness.draw().iter().any(|line| line.contains("gamma")));
+}
+
+#[test]
+fn scrolls_through_thousands_of_matches_to_the_selected_one() {
+  let shortcuts: Vec<String> = (0..5000)
+    .map(|i| format!(r#"{{ "path": "https://{i}.example", "seq": ["s{i:04}"], "kind": "url" }}"#))
+    .collect();
+  let config = format!(r#"{{ "shortcuts": [{}], "providers": {{ "calculator": false }} }}"#, shortcuts.join(","));
+  let mut harness = Harness::new(&config);
+  harness.app.selected = 4321;
+  let screen = harness.draw();
+  assert_eq!(screen[11], "(#) s4321");
+  assert_eq!(screen[3], "(#) s4313");
+  assert_eq!(harness.row_background(8), Color::DarkGray);
+}
+
+#[test]
+fn moves_the_highlight_within_the_scrolled_results() {
+  let shortcuts: Vec<String> = (0..30)
+    .map(|i| format!(r#"{{ "path": "https://{i}.example", "seq": ["s{i:02}"], "kind": "url" }}"#))
+    .collect();
+  let config = format!(r#"{{ "shortcuts": [{}], "providers": {{ "calculator": false }} }}"#, shortcuts.join(","));
+  let mut harness = Harness::new(&config);
+  for _ in 0..12 {
+    harness.press(KeyCode::Down);
+    harness.draw();
+  }
+  let screen = harness.draw();
+  assert_eq!(screen[3], "(#) s04");
+  assert_eq!(screen[11], "(#) s12");
+  assert_eq!(harness.row_background(8), Color::DarkGray);
+  // going back up moves the highlight, the results stay where they are
+  harness.press(KeyCode::Up);
+  harness.press(KeyCode::Up);
+  let screen = harness.draw();
+  assert_eq!(screen[3], "(#) s04");
+  assert_eq!(harness.row_background(6), Color::DarkGray);
+  // until the selection leaves the top
+  for _ in 0..7 {
+    harness.press(KeyCode::Up);
+  }
+  let screen = harness.draw();
+  assert_eq!(screen[3], "(#) s03");
+  assert_eq!(screen[11], "(#) s11");
+  assert_eq!(harness.row_background(0), Color::DarkGray);
+}
+
+#[test]
+fn labels_and_opens_the_rows_in_view_when_scrolled_far_down() {
+  let shortcuts: Vec<String> = (0..1000)
+    .map(|i| format!(r#"{{ "path": "https://{i}.example", "seq": ["s{i:03}"], "kind": "url" }}"#))
+    .collect();
+  let config = format!(r#"{{ "shortcuts": [{}], "providers": {{ "calculator": false }} }}"#, shortcuts.join(","));
+  let mut harness = Harness::new(&config);
+  harness.app.selected = 700;
+  harness.draw();
+  harness.key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("aa) s692"));
+  assert!(screen[5].starts_with("ad) s694"));
+  harness.type_text("ad");
+  assert_eq!(harness.launcher.opened(), ["launch s694"]);
+}
+
+#[test]
+fn highlights_the_selected_match() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  harness.draw();
+  assert_eq!(harness.row_background(0), Color::DarkGray);
+  assert_ne!(harness.row_background(1), Color::DarkGray);
+  harness.press(KeyCode::Down);
+  harness.draw();
+  assert_ne!(harness.row_background(0), Color::DarkGray);
+  assert_eq!(harness.row_background(1), Color::DarkGray);
+  // the selection stops at the last match
+  harness.press(KeyCode::Down);
+  assert_eq!(harness.app.selected, 1);
+  harness.press(KeyCode::Up);
+  assert_eq!(harness.app.selected, 0);
+}
+
+#[test]
+fn only_asks_for_a_redraw_when_something_changed() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  assert!(harness.app.dirty);
+  harness.draw();
+  for _ in 0..10 {
+    harness.app.update(Message::Tick);
+  }
+  assert!(!harness.app.dirty);
+  harness.press(KeyCode::Down);
+  assert!(harness.app.dirty);
+}
+
+#[test]
+fn replays_a_script_of_keys() {
+  let mut harness = Harness::new(CONFIG);
+  harness.replay(
+    "# copies the second match\n\
+     delay 10\n\
+     type al  # alpha and also\n\
+     wait 100\n\
+     key down\n\
+     key ctrl+y"
+  );
+  assert_eq!(harness.launcher.opened(), ["copy https://also.example"]);
+  assert!(Replay::parse("key hyper+a").is_err());
+  assert!(Replay::parse("press enter").is_err());
+}
+
+#[test]
+fn searches_with_a_bang_typed_anywhere_in_the_query() {
+  let mut harness = Harness::new(
+    r#"{
+      "shortcuts": [],
+      "bangs": { "yt": "https://www.youtube.com/results?search_query={query}" },
+      "providers": { "calculator": false }
+    }"#
+  );
+  harness.type_text("lofi !yt beats");
+  let selected = harness.app.selected_shortcut().unwrap();
+  assert_eq!(selected.path, "https://www.youtube.com/results?search_query=lofi%20beats");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["launch lofi !yt beats"]);
+}
+
+#[test]
+fn ctrl_g_labels_the_rows_to_open_one_by_its_label() {
+  let mut harness = Harness::new(CONFIG);
+  harness.key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("aa] notes"));
+  assert!(screen[5].starts_with("ad) alpha"));
+  harness.type_text("a");
+  assert!(harness.draw()[5].starts_with("ad) alpha"));
+  harness.type_text("f");
+  assert_eq!(harness.launcher.opened(), ["launch also"]);
+  assert_eq!(harness.app.selected, 0);
+  assert!(harness.app.hints.is_none() && harness.app.search_input.lines()[0].is_empty());
+
+  let mut harness = Harness::new(CONFIG);
+  harness.key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+  harness.press(KeyCode::Esc);
+  assert!(harness.app.hints.is_none() && harness.app.running);
+}
+
+#[test]
+fn ctrl_y_copies_the_selected_path_without_opening_it() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  harness.press(KeyCode::Down);
+  harness.key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL));
+  assert_eq!(harness.launcher.opened(), ["copy https://also.example"]);
+  assert!(harness.draw().iter().any(|line| line.contains("Copied https://also.example")));
+  assert!(harness.app.running);
+}
+
+#[test]
+fn ctrl_o_reveals_the_selected_file() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("no");
+  harness.key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+  assert_eq!(harness.launcher.opened(), ["reveal /nowhere/notes.txt"]);
+  assert!(!harness.app.running);
+
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  harness.key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+  assert!(harness.launcher.opened().is_empty());
+  assert!(harness.draw().iter().any(|line| line.contains("Only files and folders can be revealed")));
+}
+
+#[test]
+fn ctrl_k_offers_other_actions_on_the_selected_match() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("no");
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  let screen = harness.draw();
+  assert!(screen.iter().any(|line| line.contains("w open with…")));
+  assert!(screen.iter().any(|line| line.contains("r reveal in folder")));
+  harness.press(KeyCode::Down);
+  harness.press(KeyCode::Down);
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["copy /nowhere/notes.txt"]);
+  assert!(harness.app.action_menu.is_none());
+
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  harness.press(KeyCode::Char('w'));
+  harness.type_text("gedit");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened()[1], "open /nowhere/notes.txt with gedit");
+  assert!(!harness.app.running);
+
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  assert!(!harness.draw().iter().any(|line| line.contains("reveal in folder")));
+  harness.press(KeyCode::Esc);
+  assert!(harness.app.action_menu.is_none());
+  assert!(harness.app.running);
+}
+
+#[test]
+fn the_action_menu_acts_on_the_match_it_was_opened_on() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("no");
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  // the matches are listed again meanwhile, as a summon with a query does
+  harness.app.set_search("al");
+  assert!(harness.draw().iter().any(|line| line.contains("╭notes")));
+  harness.press(KeyCode::Char('c'));
+  assert_eq!(harness.launcher.opened(), ["copy /nowhere/notes.txt"]);
+}
+
+#[test]
+fn asks_before_deleting_a_match_from_the_config() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  harness.press(KeyCode::Char('d'));
+  assert!(harness.app.action_menu.is_none());
+  assert!(harness.draw().iter().any(|line| line.contains("Delete Alpha from the config? y/n")));
+  harness.press(KeyCode::Char('n'));
+  assert!(harness.app.pending_confirmation.is_none());
+  assert_eq!(harness.app.entries.len(), 2);
+}
+
+#[test]
+fn tab_cycles_through_the_tabs_of_the_kinds_of_the_matches() {
+  let mut harness = Harness::new(CONFIG);
+  assert!(harness.draw()[2].starts_with("╰ All 6 │ Files 2 │ URLs 4 ─"));
+  assert_eq!(tab_at(&harness.app, 10), Some(Some(ShortcutKind::File)));
+  assert_eq!(tab_at(&harness.app, 8), None);
+  harness.press(KeyCode::Tab);
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("[_] notes"));
+  assert!(screen[4].starts_with("[_] novel"));
+  assert_eq!(screen[5], "");
+  harness.press(KeyCode::Tab);
+  harness.type_text("a");
+  assert_eq!(harness.app.tabs(), [(None, 4), (Some(ShortcutKind::Url), 4)]);
+  assert!(harness.draw()[3].starts_with("(#) alpha"));
+  harness.press(KeyCode::BackTab);
+  assert_eq!(harness.app.tab, None);
+  harness.app.update(Message::SelectTab(Some(ShortcutKind::File)));
+  assert_eq!(harness.app.entries.len(), 0);
+}
+
+#[test]
+fn enter_does_the_default_action_of_the_kind() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "/nowhere/src", "seq": ["src"], "kind": "dir" },
+      { "path": "/nowhere/spec.md", "seq": ["spec"], "kind": "file" }
+    ],
+    "default_actions": { "dir": "terminal", "file": "editor" },
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("s");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["terminal /nowhere/src"]);
+
+  let mut harness = Harness::new(config);
+  harness.type_text("s");
+  harness.press(KeyCode::Down);
+  harness.key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+  assert!(harness.draw().iter().any(|line| line.contains("│ e open in editor")));
+  harness.press(KeyCode::Char('o'));
+  assert_eq!(harness.launcher.opened(), ["launch spec"]);
+}
+
+#[test]
+fn opens_the_folder_of_a_file_with_open_folder() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "/nowhere/logs/app.log", "seq": ["applog"], "kind": "file", "open": "folder" },
+      { "path": "/nowhere/logs/db.log", "seq": ["dblog"], "kind": "file" }
+    ],
+    "default_actions": { "file": "editor" },
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("log");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["reveal /nowhere/logs/app.log"]);
+
+  let mut harness = Harness::new(config);
+  harness.type_text("log");
+  harness.press(KeyCode::Down);
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["edit /nowhere/logs/db.log"]);
+}
+
+#[test]
+fn asks_before_opening_a_shortcut_with_confirm() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("g");
+  assert!(harness.draw().iter().any(|line| line.contains("Open Gamma? y/n")));
+  harness.press(KeyCode::Char('n'));
+  assert!(harness.app.pending_confirmation.is_none());
+  assert!(!harness.draw().iter().any(|line| line.contains("y/n")));
+  assert!(harness.app.running);
+}
+
+#[test]
+fn esc_and_ctrl_c_cancel() {
+  let mut harness = Harness::new(CONFIG);
+  harness.press(KeyCode::Esc);
+  assert!(!harness.app.running && harness.app.cancelled);
+
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("g");
+  harness.key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+  assert!(!harness.app.running && harness.app.cancelled);
+}
+
+#[test]
+fn shows_why_the_config_did_not_load() {
+  let missing = PathBuf::from("/nowhere/bullet/config.json");
+  let mut harness = Harness::with_config(Err(LoadConfigError::NotFound(vec![missing])));
+  let screen = harness.draw();
+  assert!(screen[3].starts_with("Config does not exist in \"/nowhere/bullet/config.json\""));
+  harness.type_text("al");
+  assert!(harness.app.matched_shortcuts.is_empty());
+}
+
+#[test]
+fn init_writes_a_config_of_the_shortcuts_it_is_told() {
+  let dir = std::env::temp_dir().join(format!("bullet-init-{}", std::process::id()));
+  let path = dir.join("config.json");
+  let answers = format!("{}\nhttps://www.github.com/me\n\nMy code\n/nowhere/todo.txt\ntodo\n\n\n", path.display());
+  let detected = Detected {
+    terminal: Some(TerminalEmulator { program: "kitty".to_string(), args: vec![] }),
+    editor: Some(("nvim".to_string(), true)),
+    ..Detected::default()
+  };
+  let mut output = vec![];
+  let written = init::run(&mut answers.as_bytes(), &mut output, &detected).unwrap();
+  assert_eq!(written, path);
+  let content = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_dir_all(&dir).unwrap();
+  assert!(content.contains(r#""//": "#));
+  let config = serde_json::from_str::<Config>(&content).unwrap();
+  let shortcuts: Vec<(&str, &str, ShortcutKind)> =
+    config.shortcuts.iter().map(|s| (s.seq[0].as_str(), s.path.as_str(), s.kind)).collect();
+  assert_eq!(shortcuts[0], ("github", "https://www.github.com/me", ShortcutKind::Url));
+  assert_eq!(config.shortcuts[0].description.as_deref(), Some("My code"));
+  assert_eq!(shortcuts[1], ("todo", "/nowhere/todo.txt", ShortcutKind::File));
+  assert_eq!(shortcuts[2], ("edit", "nvim", ShortcutKind::App));
+  assert!(config.shortcuts[2].terminal);
+  assert_eq!(shortcuts[3].0, "config");
+  assert_eq!(config.terminal.program.as_deref(), Some("kitty"));
+  assert!(config.browser.is_none());
+}
+
+#[test]
+fn ctrl_l_lists_the_last_launches() {
+  let mut harness = Harness::new(CONFIG);
+  harness.key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+  let now = chrono::Utc::now().timestamp();
+  let launch = |seq: &str, query: &str, error: Option<&str>| Launch {
+    seq: seq.to_string(),
+    path: format!("https://{seq}.example"),
+    kind: ShortcutKind::Url,
+    query: query.to_string(),
+    launched_at: now - 7200,
+    error: error.map(str::to_string),
+  };
+  harness.app.launch_log.as_mut().unwrap().launches =
+    vec![launch("beta", "be", Some("no browser")), launch("alpha", "al", None)];
+  let screen = harness.draw();
+  assert!(screen[3].contains("Last 2 launches"));
+  assert_eq!(screen[4].trim_end_matches('│').trim(), "│ 2h ago   beta  \"be\"  failed: no browser");
+  assert_eq!(screen[5].trim_end_matches('│').trim(), "│ 2h ago   alpha  \"al\"  ok");
+  harness.press(KeyCode::Down);
+  let screen = harness.draw();
+  assert!(screen[4].contains("alpha"));
+  harness.type_text("x");
+  assert_eq!(harness.app.search_input.lines()[0], "");
+  harness.press(KeyCode::Esc);
+  assert!(harness.app.launch_log.is_none() && harness.app.running);
+}
+
+#[test]
+fn opens_a_unique_match_right_away() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("b");
+  assert_eq!(harness.launcher.opened(), ["launch beta"]);
+  assert!(!harness.app.running && !harness.app.cancelled);
+}
+
+#[test]
+fn enter_opens_the_selected_match() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("al");
+  assert!(harness.launcher.opened().is_empty());
+  harness.press(KeyCode::Down);
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["launch also"]);
+}
+
+#[test]
+fn opens_urls_in_their_browser_or_the_configs() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://work.example", "seq": ["work"], "kind": "url", "browser": { "command": "msedge" } },
+      { "path": "https://home.example", "seq": ["home"], "kind": "url" }
+    ],
+    "browser": { "command": "firefox", "args": ["-P", "personal"] },
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("w");
+  let mut other = Harness::new(config);
+  other.type_text("h");
+  assert_eq!(harness.launcher.opened(), ["launch work in msedge"]);
+  assert_eq!(other.launcher.opened(), ["launch home in firefox"]);
+}
+
+#[test]
+fn fills_a_template_with_what_is_typed_after_its_seq() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://github.com/{args:raw}", "seq": ["gh"], "kind": "url", "description": "GitHub" },
+      { "path": "https://gitlab.com", "seq": ["lab"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("gh");
+  assert!(harness.launcher.opened().is_empty());
+  harness.type_text(" rust-lang/rust");
+  assert_eq!(harness.draw()[3], "(#) gh rust-lang/rust GitHub");
+  harness.key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL));
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["copy https://github.com/rust-lang/rust", "launch gh rust-lang/rust"]);
+}
+
+#[test]
+fn matches_across_case_folding_and_normalization() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://cafe.example", "seq": ["cafe\u0301"], "kind": "url", "description": "Cafe\u0301 menu" },
+      { "path": "https://moscow.example", "seq": ["москва"], "kind": "url", "description": "Москва" },
+      { "path": "https://strasse.example", "seq": ["straße"], "kind": "url", "description": "Straße" },
+      { "path": "https://kosmos.example", "seq": ["kosmos"], "kind": "url", "description": "κόσμος" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  let found = |harness: &mut Harness, typed: &str| {
+    harness.app.update_matches(typed);
+    harness.app.matched_shortcuts.iter().map(|s| s.path.clone()).collect::<Vec<_>>()
+  };
+  assert_eq!(found(&mut harness, "CAFÉ"), ["https://cafe.example"]);
+  assert_eq!(found(&mut harness, "МОСК"), ["https://moscow.example"]);
+  assert_eq!(found(&mut harness, "STRASSE"), ["https://strasse.example"]);
+  assert_eq!(found(&mut harness, "ΚΌΣΜΟΣ"), ["https://kosmos.example"]);
+}
+
+#[test]
+fn percent_encodes_what_fills_a_url_template() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "https://dict.example/?q={args}", "seq": ["dict"], "kind": "url" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("dict café & co/op");
+  harness.key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL));
+  assert_eq!(harness.launcher.opened(), ["copy https://dict.example/?q=caf%C3%A9%20%26%20co%2Fop"]);
+}
+
+#[test]
+fn opens_the_items_of_a_session_in_order() {
+  let config = r#"{
+    "shortcuts": [
+      {
+        "seq": ["work"],
+        "kind": "session",
+        "items": [
+          { "kind": "app", "path": "code", "args": ["/work/client-x"] },
+          { "kind": "url", "path": "https://staging.example", "delay": 10 },
+          { "kind": "note", "path": "/work/notes.md" },
+          { "kind": "file", "path": "/work/todo.md" }
+        ]
+      }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  assert_eq!(harness.draw()[3], "[>] work 4 items");
+  harness.type_text("w");
+  assert_eq!(harness.launcher.opened(), ["launch code", "launch https://staging.example", "launch /work/todo.md"]);
+  assert_eq!(
+    harness.app.toast.as_ref().unwrap().text,
+    "failed to open \"work\": 1 of 4 items failed (code: opened, https://staging.example: opened, \
+     /work/notes.md: sessions can't open note items, /work/todo.md: opened)"
+  );
+  assert!(harness.app.running);
+}
+
+#[test]
+fn captures_a_note_typed_after_its_seq() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "/tmp/inbox.md", "seq": ["note"], "kind": "note" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("note");
+  assert!(harness.launcher.opened().is_empty());
+  harness.type_text(" call dentist");
+  assert_eq!(harness.draw()[3], "[+] note call dentist /tmp/inbox.md");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["launch note call dentist"]);
+}
+
+#[test]
+fn expands_the_path_of_a_note_in_the_home_folder() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "~/inbox.md", "seq": ["note"], "kind": "note" },
+      { "path": "inbox.md", "seq": ["home"], "kind": "file", "path_prefix": "home" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let harness = Harness::new(config);
+  let shortcuts = harness.app.shortcuts();
+  let note = shortcuts.iter().find(|s| s.kind == ShortcutKind::Note).unwrap();
+  let home = shortcuts.iter().find(|s| s.kind == ShortcutKind::File).unwrap();
+  assert_eq!(note.prefixed_path(), home.prefixed_path());
+  assert!(!note.prefixed_path().starts_with("~"));
+}
+
+#[test]
+fn expands_the_path_of_a_script_file_but_not_an_inline_script() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "~/scripts/x.rhai", "seq": ["x"], "kind": "script" },
+      { "path": "scripts/x.rhai", "seq": ["home"], "kind": "file", "path_prefix": "home" },
+      { "path": "print(\"~/y\")", "seq": ["y"], "kind": "script" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let harness = Harness::new(config);
+  let shortcuts = harness.app.shortcuts();
+  let seq = |seq: &str| shortcuts.iter().find(|s| s.name() == seq).unwrap();
+  assert_eq!(seq("x").prefixed_path(), seq("home").prefixed_path());
+  assert_eq!(seq("y").get_prefixed_path(), r#"print("~/y")"#);
+}
+
+#[test]
+fn asks_for_the_prompted_placeholders_of_a_snippet() {
+  let config = r#"{
+    "shortcuts": [
+      { "path": "Hi {?name}, see you {?when}. {date:%Y} {?name}", "seq": ["hi"], "kind": "snippet" },
+      { "path": "Hey", "seq": ["hey"], "kind": "snippet" }
+    ],
+    "providers": { "calculator": false }
+  }"#;
+  let mut harness = Harness::new(config);
+  harness.type_text("hi");
+  assert!(harness.launcher.opened().is_empty());
+  harness.type_text("Ann");
+  assert!(harness.draw().iter().any(|line| line.contains("name: Ann_  enter/esc")));
+  harness.press(KeyCode::Enter);
+  harness.type_text("soon");
+  harness.press(KeyCode::Enter);
+  assert_eq!(harness.launcher.opened(), ["launch hi with Hi Ann, see you soon. {date:%Y} Ann"]);
+  assert!(harness.app.snippet_prompt.is_none());
+}
+
+#[test]
+fn opens_a_shortcut_with_confirm_once_confirmed() {
+  let mut harness = Harness::new(CONFIG);
+  harness.type_text("g");
+  assert!(harness.launcher.opened().is_empty());
+  harness.press(KeyCode::Char('y'));
+  assert_eq!(harness.launcher.opened(), ["launch gamma"]);
+  assert!(!harness.app.running);
+}
+
+#[test]
+fn offers_other_ways_when_opening_fails() {
+  let config = serde_json::from_str::<Config>(CONFIG).unwrap();
+  let failing = RecordingLauncher { failing: true, ..Default::default() };
+  let mut harness = Harness::with_launcher(Ok(config), failing);
+  harness.type_text("b");
+  assert!(harness.draw().iter().any(|line| line.contains("Failed to open Beta: no application")));
+  harness.press(KeyCode::Char('c'));
+  assert_eq!(harness.launcher.opened(), ["copy https://beta.example"]);
+  assert!(harness.app.failed_open.is_none() && !harness.app.running);
+}